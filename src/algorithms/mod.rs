@@ -2,5 +2,7 @@ mod traversal;
 mod property;
 
 pub use self::traversal::breadth_first_search;
+pub use self::traversal::try_breadth_first_search;
 pub use self::property::is_bipartite;
+pub use self::property::bipartite_coloring;
 pub use self::property::has_cycle;
@@ -7,9 +7,22 @@ pub fn is_bipartite<T: StaticGraph>(graph: &T) -> bool {
         return true;
     }
 
-    let mut q = VecDeque::new();
+    bipartite_coloring(graph).is_some()
+}
 
+///
+/// Two-colors the nodes reachable from node **0** such that no edge joins
+/// nodes of the same color. Returns `None` if that is impossible. Nodes that
+/// are not reached stay uncolored.
+///
+pub fn bipartite_coloring<T: StaticGraph>(graph: &T) -> Option<Vec<Option<bool>>> {
     let mut color: Vec<Option<bool>> = vec![None; graph.num_nodes()];
+
+    if graph.num_nodes() == 0 {
+        return Some(color);
+    }
+
+    let mut q = VecDeque::new();
     let mut visited = vec![false; graph.num_nodes()];
 
     visited[0] = true;
@@ -17,26 +30,23 @@ pub fn is_bipartite<T: StaticGraph>(graph: &T) -> bool {
 
     q.push_front(0);
 
-    while !q.is_empty() {
-        let v = q.pop_back().unwrap();
+    while let Some(v) = q.pop_back() {
         for u in graph.neighbors(v) {
-            if visited[u] == false {
-                color[u] = Some(!color[v].unwrap());
+            if !visited[u] {
+                color[u] = color[v].map(|c| !c);
                 visited[u] = true;
                 q.push_front(u);
-            } else {
-                if color[v] == color[u] {
-                    return false;
-                }
+            } else if color[v] == color[u] {
+                return None;
             }
         }
     }
 
-    true
+    Some(color)
 }
 
 pub fn has_cycle<T: StaticGraph>(graph: &T) -> bool {
-    if graph.num_nodes() <= 0 {
+    if graph.num_nodes() == 0 {
         return false;
     }
 
@@ -50,7 +60,7 @@ pub fn has_cycle<T: StaticGraph>(graph: &T) -> bool {
     while !q.is_empty() {
         let v = q.pop_back().unwrap();
         for u in graph.neighbors(v) {
-            if visited[u] == true {
+            if visited[u] {
                 return true;
             }
             visited[u] = true;
@@ -68,28 +78,44 @@ mod tests {
 
     use representations::EdgeList;
     use representations::AdjacencyList;
+    use algorithms::bipartite_coloring;
 
     #[test]
     fn simple() {
         let mut graph = EdgeList::new();
 
-        assert_eq!(graph.is_bipartite(), true);
-        assert_eq!(graph.has_cycle(), false);
+        assert!(graph.is_bipartite());
+        assert!(!graph.has_cycle());
 
         graph.add_edge(0,1);
 
-        assert_eq!(graph.is_bipartite(), true);
-        assert_eq!(graph.has_cycle(), false);
+        assert!(graph.is_bipartite());
+        assert!(!graph.has_cycle());
+
+        graph.add_edge(1,2);
 
+        assert!(graph.is_bipartite());
+        assert!(!graph.has_cycle());
+
+        graph.add_edge(2,0);
+
+        assert!(!graph.is_bipartite());
+        assert!(graph.has_cycle());
+    }
+
+    #[test]
+    fn coloring() {
+        let mut graph = EdgeList::new();
+
+        graph.add_edge(0,1);
         graph.add_edge(1,2);
+        graph.add_edge(4,3);
 
-        assert_eq!(graph.is_bipartite(), true);
-        assert_eq!(graph.has_cycle(), false);
+        assert_eq!(bipartite_coloring(&graph), Some(vec![Some(true), Some(false), Some(true), None, None]));
 
         graph.add_edge(2,0);
 
-        assert_eq!(graph.is_bipartite(), false);
-        assert_eq!(graph.has_cycle(), true);
+        assert_eq!(bipartite_coloring(&graph), None);
     }
 
     #[test]
@@ -101,8 +127,8 @@ mod tests {
         graph.add_edge(1,3);
         graph.add_edge(2,3);
 
-        assert_eq!(graph.is_bipartite(), true);
-        assert_eq!(graph.has_cycle(), true);
+        assert!(graph.is_bipartite());
+        assert!(graph.has_cycle());
     }
 
     #[test]
@@ -115,8 +141,8 @@ mod tests {
             }
         }
 
-        assert_eq!(graph.is_bipartite(), true);
-        assert_eq!(graph.has_cycle(), false);
+        assert!(graph.is_bipartite());
+        assert!(!graph.has_cycle());
     }
 
 
@@ -133,7 +159,7 @@ mod tests {
         graph.add_edge(70, 89);
         graph.add_edge(70, 12);
 
-        assert_eq!(graph.is_bipartite(), false);
-        assert_eq!(graph.has_cycle(), true);
+        assert!(!graph.is_bipartite());
+        assert!(graph.has_cycle());
     }
 }
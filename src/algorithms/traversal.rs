@@ -1,8 +1,16 @@
 use StaticGraph;
 use Node;
+use Result;
+use traits::check_node;
 
 use std::collections::VecDeque;
 
+///
+/// Predecessors of a breadth-first search from **start**. The start node is
+/// its own predecessor, unreached nodes have none.
+///
+/// Panics if **start** is out of range, see `try_breadth_first_search`.
+///
 pub fn breadth_first_search<T: StaticGraph>(graph: &T, start: Node) -> Vec<Option<Node>> {
     let mut q = VecDeque::new();
     let mut pred = vec![None; graph.num_nodes()];
@@ -13,7 +21,7 @@ pub fn breadth_first_search<T: StaticGraph>(graph: &T, start: Node) -> Vec<Optio
     while !q.is_empty() {
         let v = q.pop_back().unwrap();
         for u in graph.neighbors(v) {
-            if pred[u] == None {
+            if pred[u].is_none() {
                 pred[u] = Some(v);
                 q.push_front(u);
             }
@@ -23,13 +31,21 @@ pub fn breadth_first_search<T: StaticGraph>(graph: &T, start: Node) -> Vec<Optio
     pred
 }
 
+pub fn try_breadth_first_search<T: StaticGraph>(graph: &T, start: Node) -> Result<Vec<Option<Node>>> {
+    check_node(graph, start)?;
+    Ok(breadth_first_search(graph, start))
+}
+
 #[cfg(test)]
 mod tests {
     use StaticGraph;
     use Graph;
 
+    use Error;
+
     use representations::EdgeList;
     use algorithms::breadth_first_search;
+    use algorithms::try_breadth_first_search;
 
     #[test]
     fn simple() {
@@ -77,10 +93,19 @@ mod tests {
 
         let mut pred = vec![None; graph.num_nodes()];
         pred[0] = Some(0);
-        for u in 1..101 {
-            pred[u] = Some(u-1);
+        for (u, p) in pred.iter_mut().enumerate().skip(1) {
+            *p = Some(u-1);
         }
 
         assert_eq!(breadth_first_search(&graph, 0), pred);
     }
+
+    #[test]
+    fn out_of_range_start() {
+        let mut graph = EdgeList::new();
+        graph.add_edge(0,1);
+
+        assert_eq!(try_breadth_first_search(&graph, 1), Ok(vec![None, Some(1)]));
+        assert_eq!(graph.try_breadth_first_search(2), Err(Error::NodeOutOfRange { node: 2, num_nodes: 2 }));
+    }
 }
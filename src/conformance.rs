@@ -0,0 +1,378 @@
+#[cfg(test)]
+use StaticGraph;
+
+///
+/// Instantiates the conformance test suite for a `StaticGraph`
+/// implementation in a new module **name**. Graphs are built with
+/// `from_generator`, and the suite includes randomized cross-checks against
+/// `EdgeList` on seeded `Erdos` graphs.
+///
+/// The type is resolved in the enclosing module, so invoke the macro where
+/// it is in scope, typically inside a `#[cfg(test)]` module:
+///
+/// ```ignore
+/// static_graph_conformance!(my_graph, MyGraph);
+/// ```
+///
+/// Neighbors and edges are compared as multisets, so implementations are
+/// free to reorder them.
+///
+#[macro_export]
+macro_rules! static_graph_conformance {
+    ($name:ident, $graph:ty) => {
+        #[allow(unused_imports)]
+        mod $name {
+            use super::*;
+
+            $crate::static_graph_conformance!(@tests $graph);
+        }
+    };
+
+    (@tests $graph:ty) => {
+        fn build(edges: &[($crate::Node, $crate::Node)]) -> $graph {
+            let mut reference = <$crate::representations::EdgeList as $crate::Graph>::new();
+            for &(u, v) in edges {
+                $crate::Graph::add_edge(&mut reference, u, v);
+            }
+            <$graph as $crate::StaticGraph>::from_generator(&reference)
+        }
+
+        fn sorted<T: Ord, I: Iterator<Item=T>>(iter: I) -> Vec<T> {
+            let mut items = iter.collect::<Vec<T>>();
+            items.sort();
+            items
+        }
+
+        fn depths(pred: &[Option<$crate::Node>]) -> Vec<Option<usize>> {
+            (0..pred.len()).map(|mut v| {
+                let mut depth = 0;
+                loop {
+                    match pred[v] {
+                        Some(p) if p == v => return Some(depth),
+                        Some(p) => {
+                            v = p;
+                            depth += 1;
+                        }
+                        None => return None,
+                    }
+                }
+            }).collect()
+        }
+
+        fn cross_check(reference: &$crate::representations::EdgeList, graph: &$graph) {
+            use $crate::StaticGraph;
+            use $crate::Generator;
+
+            let n = reference.num_nodes();
+
+            assert_eq!(graph.num_nodes(), n);
+            assert_eq!(graph.num_edges(), reference.num_edges());
+            assert_eq!(sorted(graph.edges()), sorted(reference.edges()));
+
+            for u in 0..n + 2 {
+                assert_eq!(sorted(graph.neighbors(u)), sorted(reference.neighbors(u)), "neighbors of {}", u);
+                assert_eq!(graph.contains_node(u), u < n);
+                assert_eq!(graph.try_neighbors(u).is_ok(), u < n);
+
+                for v in 0..n + 2 {
+                    assert_eq!(graph.has_edge(u, v), reference.has_edge(u, v), "edge {} {}", u, v);
+                }
+            }
+
+            if n > 0 {
+                let start = n / 2;
+                assert_eq!(depths(&graph.breadth_first_search(start)), depths(&reference.breadth_first_search(start)));
+                assert_eq!(graph.is_bipartite(), reference.is_bipartite());
+                assert_eq!(graph.has_cycle(), reference.has_cycle());
+            }
+        }
+
+        #[test]
+        fn creation() {
+            use $crate::StaticGraph;
+
+            let graph = build(&[(0, 1)]);
+
+            assert!(graph.has_edge(0, 1));
+            assert!(!graph.has_edge(1, 0));
+            assert_eq!(graph.num_edges(), 1);
+            assert_eq!(graph.num_nodes(), 2);
+        }
+
+        #[test]
+        fn empty() {
+            use $crate::StaticGraph;
+            use $crate::Generator;
+
+            let graph = build(&[]);
+
+            assert_eq!(graph.num_edges(), 0);
+            assert_eq!(graph.num_nodes(), 0);
+            assert_eq!(graph.edges().count(), 0);
+            assert_eq!(graph.neighbors(0).count(), 0);
+            assert!(!graph.has_edge(0, 0));
+        }
+
+        #[test]
+        fn add_edges() {
+            use $crate::StaticGraph;
+
+            let graph = build(&[(0, 1), (1, 0), (2, 5), (0, 3), (5, 4)]);
+
+            assert!(graph.has_edge(2, 5));
+            assert!(!graph.has_edge(5, 2));
+            assert!(!graph.has_edge(3, 2));
+            assert_eq!(graph.num_edges(), 5);
+            assert_eq!(graph.num_nodes(), 6);
+        }
+
+        #[test]
+        fn add_many_edges() {
+            use $crate::StaticGraph;
+
+            let edges = (0..1000).flat_map(|u| (0..12).map(move |v| (u, v))).collect::<Vec<_>>();
+            let graph = build(&edges);
+
+            assert!(graph.has_edge(994, 7));
+            assert!(!graph.has_edge(994, 13));
+            assert_eq!(graph.num_edges(), 12000);
+            assert_eq!(graph.num_nodes(), 1000);
+        }
+
+        #[test]
+        fn clear_graph() {
+            use $crate::StaticGraph;
+            use $crate::Generator;
+
+            let edges = (0..100).flat_map(|u| (0..12).map(move |v| (u, v))).collect::<Vec<_>>();
+            let mut graph = build(&edges);
+
+            graph.clear();
+
+            assert!(!graph.has_edge(94, 7));
+            assert_eq!(graph.num_edges(), 0);
+            assert_eq!(graph.num_nodes(), 0);
+            assert_eq!(graph.edges().count(), 0);
+            assert_eq!(graph.neighbors(94).count(), 0);
+        }
+
+        #[test]
+        fn duplicate_edge() {
+            use $crate::StaticGraph;
+
+            let graph = build(&[(0, 1), (0, 1), (0, 1), (0, 1)]);
+
+            assert!(graph.has_edge(0, 1));
+            assert!(!graph.has_edge(1, 0));
+            assert_eq!(graph.num_edges(), 4);
+            assert_eq!(graph.num_nodes(), 2);
+            assert_eq!(graph.neighbors(0).count(), 4);
+        }
+
+        #[test]
+        fn reverse_edge() {
+            use $crate::StaticGraph;
+
+            let graph = build(&[(0, 1), (1, 0)]);
+
+            assert!(graph.has_edge(0, 1));
+            assert!(graph.has_edge(1, 0));
+            assert!(!graph.has_edge(0, 0));
+            assert!(!graph.has_edge(1, 1));
+            assert_eq!(graph.num_edges(), 2);
+        }
+
+        #[test]
+        fn multi_neighbors() {
+            use $crate::StaticGraph;
+
+            let graph = build(&[(0, 1), (0, 2), (0, 2), (0, 3), (0, 3), (0, 3), (1, 2)]);
+
+            assert_eq!(sorted(graph.neighbors(0)), vec![1, 2, 2, 3, 3, 3]);
+            assert_eq!(sorted(graph.neighbors(1)), vec![2]);
+            assert_eq!(sorted(graph.neighbors(2)), Vec::<$crate::Node>::new());
+        }
+
+        #[test]
+        fn empty_rows() {
+            use $crate::StaticGraph;
+            use $crate::Generator;
+            use $crate::Edge;
+
+            let graph = build(&[(0, 1), (2, 3), (2, 0), (5, 4)]);
+
+            assert_eq!(sorted(graph.edges()), vec![Edge::new(0, 1), Edge::new(2, 0), Edge::new(2, 3), Edge::new(5, 4)]);
+            assert_eq!(graph.neighbors(1).count(), 0);
+            assert_eq!(graph.neighbors(3).count(), 0);
+        }
+
+        #[test]
+        fn out_of_range() {
+            use $crate::StaticGraph;
+
+            let graph = build(&[(0, 1), (1, 2)]);
+
+            assert_eq!(graph.neighbors(3).count(), 0);
+            assert_eq!(graph.neighbors(usize::MAX).count(), 0);
+            assert!(!graph.has_edge(3, 0));
+            assert!(!graph.has_edge(usize::MAX, 0));
+            assert!(!graph.contains_node(3));
+            assert_eq!(graph.try_neighbors(3).err(), Some($crate::Error::NodeOutOfRange { node: 3, num_nodes: 3 }));
+            assert_eq!(graph.try_has_edge(0, 3), Err($crate::Error::NodeOutOfRange { node: 3, num_nodes: 3 }));
+            assert_eq!(graph.try_has_edge(0, 1), Ok(true));
+            assert_eq!(graph.try_breadth_first_search(3), Err($crate::Error::NodeOutOfRange { node: 3, num_nodes: 3 }));
+            assert_eq!(sorted(graph.try_neighbors(1).unwrap()), vec![2]);
+        }
+
+        #[test]
+        fn roundtrip_edgelist() {
+            use $crate::StaticGraph;
+            use $crate::Generator;
+
+            let graph = build(&[(3, 1), (0, 2), (3, 0), (1, 1), (0, 2)]);
+            let edgelist = <$crate::representations::EdgeList as StaticGraph>::from_generator(&graph);
+            let back = <$graph as StaticGraph>::from_generator(&edgelist);
+
+            assert_eq!(sorted(edgelist.edges()), sorted(graph.edges()));
+            assert_eq!(sorted(back.edges()), sorted(graph.edges()));
+        }
+
+        #[test]
+        fn algorithms() {
+            use $crate::StaticGraph;
+
+            let graph = build(&[(0, 1), (0, 2), (1, 3), (2, 3)]);
+            assert!(graph.is_bipartite());
+            assert!(graph.has_cycle());
+            assert_eq!(depths(&graph.breadth_first_search(0)), vec![Some(0), Some(1), Some(1), Some(2)]);
+
+            let graph = build(&[(0, 1), (1, 2), (2, 0)]);
+            assert!(!graph.is_bipartite());
+            assert!(graph.has_cycle());
+
+            let graph = build(&[(0, 1), (1, 2)]);
+            assert!(graph.is_bipartite());
+            assert!(!graph.has_cycle());
+        }
+
+        #[test]
+        fn randomized_against_edgelist() {
+            use $crate::StaticGraph;
+
+            for &(n, p) in &[(1, 1.0), (10, 0.3), (40, 0.05), (60, 0.2)] {
+                for seed in 0..5 {
+                    let erdos = $crate::generators::Erdos::new(n, p).with_seed(seed);
+                    let reference = <$crate::representations::EdgeList as StaticGraph>::from_generator(&erdos);
+                    let graph = <$graph as StaticGraph>::from_generator(&reference);
+
+                    cross_check(&reference, &graph);
+                }
+            }
+        }
+    };
+}
+
+///
+/// Instantiates the conformance test suite for a `Graph` implementation in
+/// a new module **name**. Runs everything of `static_graph_conformance!`
+/// and additionally checks graphs built with `new` and `add_edge`.
+///
+/// ```ignore
+/// graph_conformance!(my_graph, MyGraph);
+/// ```
+///
+#[macro_export]
+macro_rules! graph_conformance {
+    ($name:ident, $graph:ty) => {
+        #[allow(unused_imports)]
+        mod $name {
+            use super::*;
+
+            $crate::static_graph_conformance!(@tests $graph);
+
+            fn add(edges: &[($crate::Node, $crate::Node)]) -> $graph {
+                let mut graph = <$graph as $crate::Graph>::new();
+                for &(u, v) in edges {
+                    $crate::Graph::add_edge(&mut graph, u, v);
+                }
+                graph
+            }
+
+            #[test]
+            fn new_is_empty() {
+                use $crate::StaticGraph;
+                use $crate::Generator;
+
+                let graph = add(&[]);
+
+                assert_eq!(graph.num_nodes(), 0);
+                assert_eq!(graph.num_edges(), 0);
+                assert_eq!(graph.edges().count(), 0);
+            }
+
+            #[test]
+            fn add_edge() {
+                use $crate::StaticGraph;
+
+                let graph = add(&[(0, 1), (1, 0), (2, 5), (0, 3), (5, 4), (2, 5)]);
+
+                assert!(graph.has_edge(2, 5));
+                assert!(!graph.has_edge(5, 2));
+                assert_eq!(graph.num_edges(), 6);
+                assert_eq!(graph.num_nodes(), 6);
+                assert_eq!(sorted(graph.neighbors(2)), vec![5, 5]);
+            }
+
+            #[test]
+            fn add_after_clear() {
+                use $crate::StaticGraph;
+                use $crate::Graph;
+
+                let mut graph = add(&[(0, 1), (5, 6)]);
+                graph.clear();
+                graph.add_edge(1, 2);
+
+                assert!(graph.has_edge(1, 2));
+                assert!(!graph.has_edge(5, 6));
+                assert_eq!(graph.num_edges(), 1);
+                assert_eq!(graph.num_nodes(), 3);
+            }
+
+            #[test]
+            fn randomized_add_edge_against_edgelist() {
+                use $crate::StaticGraph;
+                use $crate::Generator;
+
+                for &(n, p) in &[(10, 0.3), (40, 0.05), (60, 0.2)] {
+                    let erdos = $crate::generators::Erdos::new(n, p).with_seed(n as u64);
+                    let reference = <$crate::representations::EdgeList as StaticGraph>::from_generator(&erdos);
+                    let edges = reference.edges().map(|e| (e.u(), e.v())).collect::<Vec<_>>();
+
+                    cross_check(&reference, &add(&edges));
+                }
+            }
+        }
+    };
+}
+
+///
+/// Whether **graph** has no loops or multi-edges.
+///
+#[cfg(test)]
+pub(crate) fn is_simple<T: StaticGraph>(graph: &T) -> bool {
+    (0..graph.num_nodes()).all(|v| {
+        let mut neighbors = graph.neighbors(v).collect::<Vec<_>>();
+        let count = neighbors.len();
+        neighbors.sort();
+        neighbors.dedup();
+        neighbors.len() == count && !neighbors.contains(&v)
+    })
+}
+
+///
+/// Whether **graph** holds every edge in both directions.
+///
+#[cfg(test)]
+pub(crate) fn is_symmetric<T: StaticGraph>(graph: &T) -> bool {
+    graph.edges().all(|e| graph.has_edge(e.v(), e.u()))
+}
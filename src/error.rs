@@ -0,0 +1,33 @@
+use Node;
+
+use std::error;
+use std::fmt;
+use std::result;
+
+///
+/// Errors reported by the fallible `try_` operations and constructors of
+/// gruph.
+///
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Error {
+    /// A node argument is not smaller than the number of nodes of the graph.
+    NodeOutOfRange { node: Node, num_nodes: usize },
+    /// A degree sequence can't be realized by a graph of the requested kind.
+    NotGraphical,
+    /// Generator parameters that no graph of the requested kind satisfies.
+    Infeasible,
+}
+
+pub type Result<T> = result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::NodeOutOfRange { node, num_nodes } => write!(f, "node {} out of range for graph with {} nodes", node, num_nodes),
+            Error::NotGraphical => write!(f, "degree sequence is not graphical"),
+            Error::Infeasible => write!(f, "generator parameters are infeasible"),
+        }
+    }
+}
+
+impl error::Error for Error {}
@@ -0,0 +1,506 @@
+use Generator;
+use StaticGraph;
+use Node;
+use Edge;
+
+use representations::AdjacencyList;
+use representations::Csr;
+use representations::EdgeList;
+
+use super::invalid_data;
+
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::iter;
+
+const MAGIC: &[u8; 4] = b"GRPH";
+const VERSION: u16 = 1;
+
+const FLAG_DIRECTED: u8 = 1;
+const FLAG_CHECKSUM: u8 = 2;
+
+/// Number of ids encoded or decoded per buffered chunk.
+const CHUNK: usize = 1 << 16;
+
+///
+/// How the edges of a binary graph are laid out after the header.
+///
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BinaryLayout {
+    /// `num_edges` pairs of ids in `edges()` order.
+    Edges,
+    /// `num_nodes + 1` row offsets as `u64`, then `num_edges` column ids.
+    Csr,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct BinaryOptions {
+    /// Marks the graph as directed. Edges are always stored as they are, the
+    /// flag only records how they should be interpreted.
+    pub directed: bool,
+    /// Appends a 64-bit FNV-1a checksum of the body that is verified on read.
+    pub checksum: bool,
+}
+
+impl Default for BinaryOptions {
+    fn default() -> BinaryOptions {
+        BinaryOptions { directed: true, checksum: true }
+    }
+}
+
+///
+/// The fixed 32 byte header of the native binary format. All integers are
+/// little-endian.
+///
+/// | offset | size | field                                          |
+/// |--------|------|------------------------------------------------|
+/// | 0      | 4    | magic `GRPH`                                   |
+/// | 4      | 2    | version                                        |
+/// | 6      | 1    | layout, `0` for edges and `1` for CSR          |
+/// | 7      | 1    | flags, bit 0 directed and bit 1 checksum       |
+/// | 8      | 1    | id width in bytes, `4` or `8`                  |
+/// | 9      | 7    | reserved, zero                                 |
+/// | 16     | 8    | number of nodes                                |
+/// | 24     | 8    | number of edges                                |
+///
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct BinaryHeader {
+    version: u16,
+    layout: BinaryLayout,
+    directed: bool,
+    checksum: bool,
+    id_width: usize,
+    num_nodes: usize,
+    num_edges: usize,
+}
+
+impl BinaryHeader {
+    fn new(layout: BinaryLayout, options: &BinaryOptions, num_nodes: usize, num_edges: usize) -> BinaryHeader {
+        let id_width = if num_nodes as u64 <= u64::from(u32::MAX) { 4 } else { 8 };
+        BinaryHeader { version: VERSION, layout, directed: options.directed, checksum: options.checksum, id_width, num_nodes, num_edges }
+    }
+
+    pub fn read<R: Read>(reader: &mut R) -> io::Result<BinaryHeader> {
+        let mut bytes = [0; 32];
+        reader.read_exact(&mut bytes)?;
+
+        if &bytes[0..4] != MAGIC {
+            return Err(invalid_data("not a gruph binary graph"));
+        }
+
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != VERSION {
+            return Err(invalid_data(format!("unsupported binary version {}", version)));
+        }
+
+        let layout = match bytes[6] {
+            0 => BinaryLayout::Edges,
+            1 => BinaryLayout::Csr,
+            other => return Err(invalid_data(format!("unknown binary layout {}", other))),
+        };
+
+        let id_width = bytes[8] as usize;
+        if id_width != 4 && id_width != 8 {
+            return Err(invalid_data(format!("unsupported id width {}", id_width)));
+        }
+
+        Ok(BinaryHeader {
+            version,
+            layout,
+            directed: bytes[7] & FLAG_DIRECTED != 0,
+            checksum: bytes[7] & FLAG_CHECKSUM != 0,
+            id_width,
+            num_nodes: to_usize(read_u64(&bytes[16..24]))?,
+            num_edges: to_usize(read_u64(&bytes[24..32]))?,
+        })
+    }
+
+    fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut bytes = [0; 32];
+
+        bytes[0..4].copy_from_slice(MAGIC);
+        bytes[4..6].copy_from_slice(&self.version.to_le_bytes());
+        bytes[6] = match self.layout {
+            BinaryLayout::Edges => 0,
+            BinaryLayout::Csr => 1,
+        };
+        bytes[7] = if self.directed { FLAG_DIRECTED } else { 0 } | if self.checksum { FLAG_CHECKSUM } else { 0 };
+        bytes[8] = self.id_width as u8;
+        bytes[16..24].copy_from_slice(&(self.num_nodes as u64).to_le_bytes());
+        bytes[24..32].copy_from_slice(&(self.num_edges as u64).to_le_bytes());
+
+        writer.write_all(&bytes)
+    }
+
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    pub fn layout(&self) -> BinaryLayout {
+        self.layout
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn has_checksum(&self) -> bool {
+        self.checksum
+    }
+
+    pub fn id_width(&self) -> usize {
+        self.id_width
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.num_nodes
+    }
+
+    pub fn num_edges(&self) -> usize {
+        self.num_edges
+    }
+}
+
+///
+/// Compact native serialization. Writing and reading the same representation
+/// round-trips exactly, including edge order. `AdjacencyList` and `Csr` also
+/// keep the node count, while `EdgeList` has none of its own and drops
+/// isolated nodes past its largest endpoint. Any representation can read
+/// files written by any other.
+///
+/// `EdgeList` and `AdjacencyList` use the `Edges` layout, `Csr` stores its
+/// arrays directly. Wrap files in `BufReader` and `BufWriter`.
+///
+pub trait BinaryFormat: Sized {
+    fn write_binary<W: Write>(&self, writer: &mut W, options: &BinaryOptions) -> io::Result<()>;
+    fn read_binary<R: Read>(reader: &mut R) -> io::Result<Self>;
+}
+
+impl BinaryFormat for EdgeList {
+    fn write_binary<W: Write>(&self, writer: &mut W, options: &BinaryOptions) -> io::Result<()> {
+        write_edges(self, writer, options)
+    }
+
+    fn read_binary<R: Read>(reader: &mut R) -> io::Result<EdgeList> {
+        Ok(EdgeList::from_generator(&read_body(reader)?))
+    }
+}
+
+impl BinaryFormat for AdjacencyList {
+    fn write_binary<W: Write>(&self, writer: &mut W, options: &BinaryOptions) -> io::Result<()> {
+        write_edges(self, writer, options)
+    }
+
+    fn read_binary<R: Read>(reader: &mut R) -> io::Result<AdjacencyList> {
+        Ok(AdjacencyList::from_generator(&read_body(reader)?))
+    }
+}
+
+impl BinaryFormat for Csr {
+    fn write_binary<W: Write>(&self, writer: &mut W, options: &BinaryOptions) -> io::Result<()> {
+        let header = BinaryHeader::new(BinaryLayout::Csr, options, self.num_nodes(), self.num_edges());
+        header.write(writer)?;
+
+        let mut body = BodyWriter { writer, hash: FNV_OFFSET, buf: vec![] };
+        let row = if self.row().is_empty() { &[0][..] } else { self.row() };
+        body.write_ids(row.iter().cloned(), 8)?;
+        body.write_ids(self.col().iter().cloned(), header.id_width)?;
+        body.finish(header.checksum)
+    }
+
+    fn read_binary<R: Read>(reader: &mut R) -> io::Result<Csr> {
+        match read_body(reader)? {
+            Body { row: Some(row), col, .. } => Ok(Csr::from_raw_parts(row, col)),
+            body => Ok(Csr::from_generator(&body)),
+        }
+    }
+}
+
+fn write_edges<T: StaticGraph, W: Write>(graph: &T, writer: &mut W, options: &BinaryOptions) -> io::Result<()> {
+    let header = BinaryHeader::new(BinaryLayout::Edges, options, graph.num_nodes(), graph.num_edges());
+    header.write(writer)?;
+
+    let mut body = BodyWriter { writer, hash: FNV_OFFSET, buf: vec![] };
+    body.write_ids(graph.edges().flat_map(|e| iter::once(e.u()).chain(iter::once(e.v()))), header.id_width)?;
+    body.finish(header.checksum)
+}
+
+///
+/// Decoded body. For the `Edges` layout, **col** holds the flattened id
+/// pairs and **row** is `None`.
+///
+struct Body {
+    num_nodes: usize,
+    row: Option<Vec<usize>>,
+    col: Vec<Node>,
+}
+
+impl Generator for Body {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        match self.row {
+            Some(ref row) => Box::new((0..self.num_nodes).flat_map(move |u| self.col[row[u]..row[u + 1]].iter().map(move |&v| Edge::new(u, v)))),
+            None => Box::new(self.col.chunks(2).map(|pair| Edge::new(pair[0], pair[1]))),
+        }
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.num_nodes)
+    }
+}
+
+fn read_body<R: Read>(reader: &mut R) -> io::Result<Body> {
+    let header = BinaryHeader::read(reader)?;
+    let mut body = BodyReader { reader, hash: FNV_OFFSET };
+    let n = header.num_nodes;
+    let m = header.num_edges;
+
+    let row = match header.layout {
+        BinaryLayout::Csr => {
+            let row = body.read_ids(n.checked_add(1).ok_or_else(|| invalid_data("too many nodes"))?, 8)?;
+            if row[0] != 0 || row.windows(2).any(|w| w[0] > w[1]) || row[n] != m {
+                return Err(invalid_data("inconsistent row offsets"));
+            }
+            Some(row)
+        }
+        BinaryLayout::Edges => None,
+    };
+
+    let num_ids = if row.is_some() { m } else { m.checked_mul(2).ok_or_else(|| invalid_data("too many edges"))? };
+    let col = body.read_ids(num_ids, header.id_width)?;
+
+    if let Some(&v) = col.iter().find(|&&v| v >= n) {
+        return Err(invalid_data(format!("node {} out of range", v)));
+    }
+
+    if header.checksum {
+        let expected = body.hash;
+        let mut bytes = [0; 8];
+        body.reader.read_exact(&mut bytes)?;
+        if read_u64(&bytes) != expected {
+            return Err(invalid_data("checksum mismatch"));
+        }
+    }
+
+    Ok(Body { num_nodes: n, row, col })
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &b in bytes {
+        hash ^= u64::from(b);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+struct BodyWriter<'a, W: 'a> {
+    writer: &'a mut W,
+    hash: u64,
+    buf: Vec<u8>,
+}
+
+impl<'a, W: Write> BodyWriter<'a, W> {
+    fn write_ids<I: Iterator<Item=usize>>(&mut self, ids: I, width: usize) -> io::Result<()> {
+        for id in ids {
+            self.buf.extend_from_slice(&(id as u64).to_le_bytes()[..width]);
+            if self.buf.len() >= CHUNK * 8 {
+                self.flush()?;
+            }
+        }
+        self.flush()
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.hash = fnv1a(self.hash, &self.buf);
+        self.writer.write_all(&self.buf)?;
+        self.buf.clear();
+        Ok(())
+    }
+
+    fn finish(self, checksum: bool) -> io::Result<()> {
+        if checksum {
+            self.writer.write_all(&self.hash.to_le_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+struct BodyReader<'a, R: 'a> {
+    reader: &'a mut R,
+    hash: u64,
+}
+
+impl<'a, R: Read> BodyReader<'a, R> {
+    fn read_ids(&mut self, count: usize, width: usize) -> io::Result<Vec<usize>> {
+        // The count comes from the file, so only reserve what a chunk can fill.
+        let mut ids = Vec::with_capacity(count.min(CHUNK));
+        let mut buf = vec![0; CHUNK * width];
+        let mut remaining = count;
+
+        while remaining > 0 {
+            let len = remaining.min(CHUNK);
+            let bytes = &mut buf[..len * width];
+            self.reader.read_exact(bytes)?;
+            self.hash = fnv1a(self.hash, bytes);
+
+            for id in bytes.chunks(width) {
+                let mut wide = [0; 8];
+                wide[..width].copy_from_slice(id);
+                ids.push(to_usize(u64::from_le_bytes(wide))?);
+            }
+
+            remaining -= len;
+        }
+
+        Ok(ids)
+    }
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    let mut wide = [0; 8];
+    wide.copy_from_slice(bytes);
+    u64::from_le_bytes(wide)
+}
+
+fn to_usize(value: u64) -> io::Result<usize> {
+    if value > usize::MAX as u64 {
+        return Err(invalid_data(format!("value {} does not fit this platform", value)));
+    }
+    Ok(value as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use StaticGraph;
+    use Generator;
+    use Graph;
+    use Edge;
+
+    use representations::AdjacencyList;
+    use representations::Csr;
+    use representations::EdgeList;
+    use generators::Erdos;
+    use formats::BinaryFormat;
+    use formats::BinaryHeader;
+    use formats::BinaryLayout;
+    use formats::BinaryOptions;
+    use formats::MatrixMarket;
+
+    fn write<T: BinaryFormat>(graph: &T, options: &BinaryOptions) -> Vec<u8> {
+        let mut buf = vec![];
+        graph.write_binary(&mut buf, options).unwrap();
+        buf
+    }
+
+    #[test]
+    fn roundtrip_edgelist() {
+        let graph = EdgeList::from_generator(&Erdos::new(100, 0.05));
+        let read = EdgeList::read_binary(&mut &write(&graph, &BinaryOptions::default())[..]).unwrap();
+
+        assert_eq!(read.edges().collect::<Vec<Edge>>(), graph.edges().collect::<Vec<Edge>>());
+    }
+
+    #[test]
+    fn roundtrip_adjacencylist() {
+        let mut graph = AdjacencyList::new();
+        graph.add_edge(3, 1);
+        graph.add_edge(0, 7);
+        graph.add_edge(3, 0);
+        graph.add_edge(3, 1);
+
+        let read = AdjacencyList::read_binary(&mut &write(&graph, &BinaryOptions::default())[..]).unwrap();
+
+        assert_eq!(read.num_nodes(), 8);
+        assert_eq!(read.edges().collect::<Vec<Edge>>(), graph.edges().collect::<Vec<Edge>>());
+    }
+
+    #[test]
+    fn roundtrip_csr() {
+        let input = "%%MatrixMarket matrix coordinate pattern general\n10 10 3\n2 1\n2 5\n7 7\n";
+        let graph = Csr::from_generator(&MatrixMarket::read(input.as_bytes()).unwrap());
+        let buf = write(&graph, &BinaryOptions { directed: false, checksum: false });
+
+        let header = BinaryHeader::read(&mut &buf[..]).unwrap();
+        assert_eq!(header.layout(), BinaryLayout::Csr);
+        assert!(!header.is_directed());
+        assert!(!header.has_checksum());
+        assert_eq!(header.id_width(), 4);
+        assert_eq!(header.num_nodes(), 10);
+        assert_eq!(header.num_edges(), 3);
+        assert_eq!(buf.len(), 32 + 11 * 8 + 3 * 4);
+
+        let read = Csr::read_binary(&mut &buf[..]).unwrap();
+        assert_eq!(read.row(), graph.row());
+        assert_eq!(read.col(), graph.col());
+    }
+
+    #[test]
+    fn isolated_last_node() {
+        let graph = Csr::from_raw_parts(vec![0, 1, 2, 2, 2, 2], vec![1, 0]);
+        let buf = write(&graph, &BinaryOptions::default());
+
+        let al = AdjacencyList::read_binary(&mut &buf[..]).unwrap();
+        let csr = Csr::read_binary(&mut &write(&al, &BinaryOptions::default())[..]).unwrap();
+        let el = EdgeList::read_binary(&mut &buf[..]).unwrap();
+
+        assert_eq!(al.num_nodes(), 5);
+        assert_eq!(csr.num_nodes(), 5);
+        assert_eq!(csr.row(), graph.row());
+        assert_eq!(el.num_nodes(), 2);
+        assert_eq!(el.edges().collect::<Vec<Edge>>(), graph.edges().collect::<Vec<Edge>>());
+    }
+
+    #[test]
+    fn across_representations() {
+        let graph = Csr::from_generator(&Erdos::new(50, 0.1));
+        let buf = write(&graph, &BinaryOptions::default());
+
+        let el = EdgeList::read_binary(&mut &buf[..]).unwrap();
+        let al = AdjacencyList::read_binary(&mut &write(&el, &BinaryOptions::default())[..]).unwrap();
+
+        assert_eq!(el.edges().collect::<Vec<Edge>>(), graph.edges().collect::<Vec<Edge>>());
+        assert_eq!(al.edges().collect::<Vec<Edge>>(), graph.edges().collect::<Vec<Edge>>());
+    }
+
+    #[test]
+    fn empty_graphs() {
+        let mut csr = Csr::from_generator(&EdgeList::new());
+        csr.clear();
+
+        let read = Csr::read_binary(&mut &write(&csr, &BinaryOptions::default())[..]).unwrap();
+        assert_eq!(read.num_nodes(), 0);
+        assert_eq!(read.num_edges(), 0);
+
+        let read = EdgeList::read_binary(&mut &write(&EdgeList::new(), &BinaryOptions::default())[..]).unwrap();
+        assert_eq!(read.num_edges(), 0);
+    }
+
+    #[test]
+    fn corruption_detected() {
+        let mut graph = EdgeList::new();
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 2);
+        let buf = write(&graph, &BinaryOptions::default());
+
+        let mut flipped = buf.clone();
+        flipped[33] ^= 1;
+        assert!(EdgeList::read_binary(&mut &flipped[..]).is_err());
+
+        let mut bad_magic = buf.clone();
+        bad_magic[0] = b'X';
+        assert!(EdgeList::read_binary(&mut &bad_magic[..]).is_err());
+
+        let mut bad_version = buf.clone();
+        bad_version[4] = 9;
+        assert!(EdgeList::read_binary(&mut &bad_version[..]).is_err());
+
+        assert!(EdgeList::read_binary(&mut &buf[..buf.len() - 1]).is_err());
+
+        let mut out_of_range = write(&graph, &BinaryOptions { directed: true, checksum: false });
+        out_of_range[32] = 200;
+        assert!(EdgeList::read_binary(&mut &out_of_range[..]).is_err());
+    }
+}
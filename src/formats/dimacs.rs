@@ -0,0 +1,335 @@
+use Generator;
+use StaticGraph;
+use Node;
+use Edge;
+
+use super::invalid_data;
+
+use std::collections::HashSet;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+
+///
+/// The problem line variants of the DIMACS challenge formats.
+///
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum DimacsProblem {
+    /// `p sp`: shortest paths, arcs carry a length.
+    ShortestPath,
+    /// `p max`: maximum flow, arcs carry a capacity and the file designates
+    /// a source and a sink.
+    MaxFlow,
+    /// `p edge`: graph coloring, undirected edges without weights.
+    Edge,
+}
+
+impl DimacsProblem {
+    fn keyword(&self) -> &'static str {
+        match *self {
+            DimacsProblem::ShortestPath => "sp",
+            DimacsProblem::MaxFlow => "max",
+            DimacsProblem::Edge => "edge",
+        }
+    }
+}
+
+///
+/// Graph in one of the [DIMACS](http://dimacs.rutgers.edu/programs/challenge/)
+/// challenge formats. Nodes are 1-based in the file and 0-based in gruph.
+///
+/// Arcs (`a u v w`) are directed. Edges of the coloring format (`e u v`) are
+/// undirected and generated in both directions. Missing weights are written
+/// as **1**.
+///
+pub struct Dimacs {
+    problem: DimacsProblem,
+    num_nodes: usize,
+    arcs: Vec<Edge>,
+    weights: Option<Vec<i64>>,
+    source: Option<Node>,
+    sink: Option<Node>,
+}
+
+impl Dimacs {
+    ///
+    /// Takes the edges of **graph** without weights. For the coloring format
+    /// an edge present in both directions is only kept once.
+    ///
+    pub fn new<T: StaticGraph>(problem: DimacsProblem, graph: &T) -> Dimacs {
+        let mut arcs = graph.edges().collect::<Vec<Edge>>();
+        if problem == DimacsProblem::Edge {
+            let forward = arcs.iter().filter(|e| e.u() < e.v()).map(|e| (e.u(), e.v())).collect::<HashSet<(Node, Node)>>();
+            arcs.retain(|e| e.u() <= e.v() || !forward.contains(&(e.v(), e.u())));
+        }
+
+        Dimacs { problem, num_nodes: graph.num_nodes(), arcs, weights: None, source: None, sink: None }
+    }
+
+    ///
+    /// Attaches arc lengths or capacities, in the order of `edges()`. Fails
+    /// unless there is exactly one weight per arc.
+    ///
+    pub fn with_weights(mut self, weights: Vec<i64>) -> io::Result<Dimacs> {
+        if weights.len() != self.arcs.len() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "one weight per arc required"));
+        }
+        self.weights = Some(weights);
+        Ok(self)
+    }
+
+    pub fn with_source_sink(mut self, source: Node, sink: Node) -> Dimacs {
+        self.source = Some(source);
+        self.sink = Some(sink);
+        self
+    }
+
+    pub fn read<R: BufRead>(reader: R) -> io::Result<Dimacs> {
+        let mut header: Option<(DimacsProblem, usize, usize)> = None;
+        let mut arcs = vec![];
+        let mut weights = vec![];
+        let mut source = None;
+        let mut sink = None;
+
+        for line in reader.lines() {
+            let line = line?;
+            let tokens = line.split_whitespace().collect::<Vec<&str>>();
+
+            if tokens.is_empty() || tokens[0] == "c" {
+                continue;
+            }
+
+            if tokens[0] == "p" {
+                if header.is_some() {
+                    return Err(invalid_data("duplicate problem line"));
+                }
+                if tokens.len() != 4 {
+                    return Err(invalid_data(format!("invalid problem line '{}'", line)));
+                }
+                let problem = match tokens[1] {
+                    "sp" => DimacsProblem::ShortestPath,
+                    "max" => DimacsProblem::MaxFlow,
+                    "edge" | "col" => DimacsProblem::Edge,
+                    other => return Err(invalid_data(format!("unsupported problem '{}'", other))),
+                };
+                header = Some((problem, parse_number(tokens[2])?, parse_number(tokens[3])?));
+                continue;
+            }
+
+            let (problem, num_nodes, _) = match header {
+                Some(header) => header,
+                None => return Err(invalid_data("missing problem line")),
+            };
+
+            match (problem, tokens[0]) {
+                (DimacsProblem::ShortestPath, "a") | (DimacsProblem::MaxFlow, "a") => {
+                    if tokens.len() != 4 {
+                        return Err(invalid_data(format!("invalid arc line '{}'", line)));
+                    }
+                    arcs.push(Edge::new(parse_node(tokens[1], num_nodes)?, parse_node(tokens[2], num_nodes)?));
+                    weights.push(tokens[3].parse::<i64>().map_err(|_| invalid_data(format!("invalid weight '{}'", tokens[3])))?);
+                }
+                (DimacsProblem::Edge, "e") => {
+                    if tokens.len() != 3 {
+                        return Err(invalid_data(format!("invalid edge line '{}'", line)));
+                    }
+                    arcs.push(Edge::new(parse_node(tokens[1], num_nodes)?, parse_node(tokens[2], num_nodes)?));
+                }
+                (DimacsProblem::MaxFlow, "n") => {
+                    if tokens.len() != 3 {
+                        return Err(invalid_data(format!("invalid node line '{}'", line)));
+                    }
+                    let node = parse_node(tokens[1], num_nodes)?;
+                    match tokens[2] {
+                        "s" => source = Some(node),
+                        "t" => sink = Some(node),
+                        other => return Err(invalid_data(format!("invalid node designator '{}'", other))),
+                    }
+                }
+                _ => return Err(invalid_data(format!("unexpected line '{}'", line))),
+            }
+        }
+
+        let (problem, num_nodes, num_arcs) = match header {
+            Some(header) => header,
+            None => return Err(invalid_data("missing problem line")),
+        };
+
+        if arcs.len() != num_arcs {
+            return Err(invalid_data(format!("expected {} arcs, found {}", num_arcs, arcs.len())));
+        }
+
+        if problem == DimacsProblem::MaxFlow && (source.is_none() || sink.is_none()) {
+            return Err(invalid_data("missing source or sink designator"));
+        }
+
+        let weights = if problem == DimacsProblem::Edge { None } else { Some(weights) };
+
+        Ok(Dimacs { problem, num_nodes, arcs, weights, source, sink })
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "p {} {} {}", self.problem.keyword(), self.num_nodes, self.arcs.len())?;
+
+        if self.problem == DimacsProblem::MaxFlow {
+            match (self.source, self.sink) {
+                (Some(s), Some(t)) => {
+                    writeln!(writer, "n {} s", s + 1)?;
+                    writeln!(writer, "n {} t", t + 1)?;
+                }
+                _ => return Err(invalid_data("max flow problem without source or sink")),
+            }
+        }
+
+        for (i, e) in self.arcs.iter().enumerate() {
+            if self.problem == DimacsProblem::Edge {
+                writeln!(writer, "e {} {}", e.u() + 1, e.v() + 1)?;
+            } else {
+                let w = self.weights.as_ref().map_or(1, |w| w[i]);
+                writeln!(writer, "a {} {} {}", e.u() + 1, e.v() + 1, w)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn problem(&self) -> DimacsProblem {
+        self.problem
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.num_nodes
+    }
+
+    ///
+    /// Arc lengths or capacities in the order of `edges()`. `None` for the
+    /// coloring format or when no weights were attached.
+    ///
+    pub fn weights(&self) -> Option<&[i64]> {
+        self.weights.as_ref().map(|w| &w[..])
+    }
+
+    pub fn source(&self) -> Option<Node> {
+        self.source
+    }
+
+    pub fn sink(&self) -> Option<Node> {
+        self.sink
+    }
+}
+
+impl Generator for Dimacs {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        if self.problem == DimacsProblem::Edge {
+            Box::new(self.arcs.iter().flat_map(|e| {
+                let reverse = if e.u() != e.v() { Some(Edge::new(e.v(), e.u())) } else { None };
+                Some(*e).into_iter().chain(reverse)
+            }))
+        } else {
+            Box::new(self.arcs.iter().cloned())
+        }
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.num_nodes)
+    }
+}
+
+fn parse_number(token: &str) -> io::Result<usize> {
+    token.parse::<usize>().map_err(|_| invalid_data(format!("invalid number '{}'", token)))
+}
+
+fn parse_node(token: &str, num_nodes: usize) -> io::Result<Node> {
+    let node = parse_number(token)?;
+    if node == 0 || node > num_nodes {
+        return Err(invalid_data(format!("node {} out of range", node)));
+    }
+
+    Ok(node - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use StaticGraph;
+    use Generator;
+    use Graph;
+    use Edge;
+
+    use representations::Csr;
+    use representations::EdgeList;
+    use formats::Dimacs;
+    use formats::DimacsProblem;
+
+    #[test]
+    fn shortest_path() {
+        let input = "c road network\np sp 4 3\na 1 2 10\na 2 3 5\nc trailing\na 3 1 7\n";
+        let dimacs = Dimacs::read(input.as_bytes()).unwrap();
+        let graph = Csr::from_generator(&dimacs);
+
+        assert_eq!(dimacs.problem(), DimacsProblem::ShortestPath);
+        assert_eq!(graph.num_nodes(), 4);
+        assert_eq!(graph.num_edges(), 3);
+        assert!(graph.has_edge(2, 0));
+        assert_eq!(dimacs.weights().unwrap(), &[10, 5, 7]);
+    }
+
+    #[test]
+    fn max_flow() {
+        let input = "p max 3 2\nn 1 s\nn 3 t\na 1 2 4\na 2 3 2\n";
+        let dimacs = Dimacs::read(input.as_bytes()).unwrap();
+
+        assert_eq!(dimacs.problem(), DimacsProblem::MaxFlow);
+        assert_eq!(dimacs.source(), Some(0));
+        assert_eq!(dimacs.sink(), Some(2));
+        assert_eq!(dimacs.weights().unwrap(), &[4, 2]);
+    }
+
+    #[test]
+    fn coloring_is_undirected() {
+        let input = "c triangle\np edge 3 3\ne 1 2\ne 2 3\ne 3 1\n";
+        let dimacs = Dimacs::read(input.as_bytes()).unwrap();
+        let graph = Csr::from_generator(&dimacs);
+
+        assert_eq!(graph.num_edges(), 6);
+        assert!(graph.has_edge(1, 0));
+        assert!(!graph.is_bipartite());
+        assert!(dimacs.weights().is_none());
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut graph = EdgeList::new();
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 0);
+        graph.add_edge(1, 2);
+
+        let dimacs = Dimacs::new(DimacsProblem::MaxFlow, &graph).with_weights(vec![3, 1, 4]).unwrap().with_source_sink(0, 2);
+        let mut buf = vec![];
+        dimacs.write(&mut buf).unwrap();
+        let read = Dimacs::read(&buf[..]).unwrap();
+
+        assert_eq!(read.edges().collect::<Vec<Edge>>(), graph.edges().collect::<Vec<Edge>>());
+        assert_eq!(read.weights().unwrap(), &[3, 1, 4]);
+        assert_eq!(read.source(), Some(0));
+        assert_eq!(read.sink(), Some(2));
+
+        let dimacs = Dimacs::new(DimacsProblem::Edge, &graph);
+        let mut buf = vec![];
+        dimacs.write(&mut buf).unwrap();
+
+        assert_eq!(String::from_utf8(buf).unwrap(), "p edge 3 2\ne 1 2\ne 2 3\n");
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert!(Dimacs::read("a 1 2 3\n".as_bytes()).is_err());
+        assert!(Dimacs::read("p tsp 3 1\n".as_bytes()).is_err());
+        assert!(Dimacs::read("p sp 2 1\na 1 3 1\n".as_bytes()).is_err());
+        assert!(Dimacs::read("p sp 2 2\na 1 2 1\n".as_bytes()).is_err());
+        assert!(Dimacs::read("p max 2 1\nn 1 s\na 1 2 1\n".as_bytes()).is_err());
+        assert!(Dimacs::read("p edge 2 1\na 1 2 1\n".as_bytes()).is_err());
+
+        let graph = Csr::from_generator(&Dimacs::read("p sp 2 1\na 1 2 1\n".as_bytes()).unwrap());
+        assert!(Dimacs::new(DimacsProblem::ShortestPath, &graph).with_weights(vec![1, 2]).is_err());
+    }
+}
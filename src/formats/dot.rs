@@ -0,0 +1,550 @@
+use Generator;
+use StaticGraph;
+use Node;
+use Edge;
+
+use super::Attributes;
+use super::invalid_data;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::io::Write;
+
+///
+/// Writes a graph in the [DOT language](https://graphviz.org/doc/info/lang.html)
+/// of Graphviz. Created by `to_dot`.
+///
+/// The output is a `digraph` by default. In `undirected` mode it is a
+/// `graph` and an edge present in both directions is written only once.
+/// Every node gets its own statement, so isolated nodes are kept.
+///
+pub struct Dot<'a, T: 'a> {
+    graph: &'a T,
+    directed: bool,
+    node_attributes: Option<Box<dyn Fn(Node) -> Attributes + 'a>>,
+    edge_attributes: Option<Box<dyn Fn(Edge) -> Attributes + 'a>>,
+}
+
+pub fn to_dot<T: StaticGraph>(graph: &T) -> Dot<'_, T> {
+    Dot { graph, directed: true, node_attributes: None, edge_attributes: None }
+}
+
+impl<'a, T: StaticGraph> Dot<'a, T> {
+    pub fn undirected(mut self) -> Self {
+        self.directed = false;
+        self
+    }
+
+    pub fn node_attributes<F: Fn(Node) -> Attributes + 'a>(mut self, f: F) -> Self {
+        self.node_attributes = Some(Box::new(f));
+        self
+    }
+
+    pub fn edge_attributes<F: Fn(Edge) -> Attributes + 'a>(mut self, f: F) -> Self {
+        self.edge_attributes = Some(Box::new(f));
+        self
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "{}", self)
+    }
+}
+
+impl<'a, T: StaticGraph> fmt::Display for Dot<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (keyword, op) = if self.directed { ("digraph", "->") } else { ("graph", "--") };
+
+        writeln!(f, "{} {{", keyword)?;
+
+        for v in 0..self.graph.num_nodes() {
+            let attributes = self.node_attributes.as_ref().map_or(vec![], |a| a(v));
+            writeln!(f, "  {}{};", v, AttributeList(&attributes))?;
+        }
+
+        for e in self.graph.edges() {
+            if !self.directed && e.u() > e.v() && self.graph.has_edge(e.v(), e.u()) {
+                continue;
+            }
+
+            let attributes = self.edge_attributes.as_ref().map_or(vec![], |a| a(e));
+            writeln!(f, "  {} {} {}{};", e.u(), op, e.v(), AttributeList(&attributes))?;
+        }
+
+        writeln!(f, "}}")
+    }
+}
+
+struct AttributeList<'a>(&'a Attributes);
+
+impl<'a> fmt::Display for AttributeList<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.is_empty() {
+            return Ok(());
+        }
+
+        write!(f, " [")?;
+        for (i, (key, value)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}=\"{}\"", key, value.replace('\\', "\\\\").replace('"', "\\\""))?;
+        }
+        write!(f, "]")
+    }
+}
+
+///
+/// Edge attributes that highlight the tree edges of a predecessor vector as
+/// returned by `breadth_first_search`. An edge is highlighted if either of
+/// its directions is a tree edge, which also covers `undirected` output.
+///
+pub fn highlight_tree(pred: &[Option<Node>]) -> impl Fn(Edge) -> Attributes + '_ {
+    move |e| {
+        let tree = |u: Node, v: Node| u != v && pred.get(v).cloned() == Some(Some(u));
+
+        if tree(e.u(), e.v()) || tree(e.v(), e.u()) {
+            vec![("color".to_string(), "red".to_string()), ("penwidth".to_string(), "2".to_string())]
+        } else {
+            vec![]
+        }
+    }
+}
+
+///
+/// Node attributes that fill the two color classes of a coloring as returned
+/// by `bipartite_coloring`. Uncolored nodes are left unstyled.
+///
+pub fn color_classes(colors: &[Option<bool>]) -> impl Fn(Node) -> Attributes + '_ {
+    move |v| {
+        match colors.get(v).cloned() {
+            Some(Some(class)) => {
+                let fill = if class { "lightblue" } else { "lightpink" };
+                vec![("style".to_string(), "filled".to_string()), ("fillcolor".to_string(), fill.to_string())]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+///
+/// Graph parsed from the edge subset of the DOT language: node and edge
+/// statements, edge chains like `a -> b -> c`, and comments. Attribute lists
+/// and graph attributes are accepted but ignored, subgraphs are rejected.
+///
+/// Node names are mapped to dense ids in order of first appearance, so the
+/// output of `to_dot` parses back to the same ids. Edges of an undirected
+/// `graph` are generated in both directions.
+///
+pub struct DotGraph {
+    directed: bool,
+    names: Vec<String>,
+    edges: Vec<Edge>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Token {
+    Id(String),
+    Arrow,
+    Line,
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Equals,
+    Separator,
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn next(&mut self, expected: &str) -> io::Result<Token> {
+        let token = self.tokens.get(self.pos).cloned()
+            .ok_or_else(|| invalid_data(format!("expected {}, found end of input", expected)))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn back(&mut self) {
+        self.pos -= 1;
+    }
+
+    fn skip_attributes(&mut self) -> io::Result<()> {
+        loop {
+            match self.next("]")? {
+                Token::RBracket => return Ok(()),
+                Token::Id(_) | Token::Equals | Token::Separator => {}
+                other => return Err(invalid_data(format!("unexpected {:?} in attribute list", other))),
+            }
+        }
+    }
+}
+
+impl DotGraph {
+    pub fn parse(input: &str) -> io::Result<DotGraph> {
+        let mut parser = Parser { tokens: tokenize(input)?, pos: 0 };
+
+        let mut token = parser.next("graph")?;
+        if is_keyword(&token, "strict") {
+            token = parser.next("graph")?;
+        }
+
+        let directed = if is_keyword(&token, "digraph") {
+            true
+        } else if is_keyword(&token, "graph") {
+            false
+        } else {
+            return Err(invalid_data(format!("expected graph or digraph, found {:?}", token)));
+        };
+
+        token = parser.next("{")?;
+        if let Token::Id(_) = token {
+            token = parser.next("{")?;
+        }
+        if token != Token::LBrace {
+            return Err(invalid_data(format!("expected {{, found {:?}", token)));
+        }
+
+        let mut graph = DotGraph { directed, names: vec![], edges: vec![] };
+        let mut ids = HashMap::new();
+
+        loop {
+            let statement = parser.next("}")?;
+
+            match statement {
+                Token::RBrace => break,
+                Token::Separator => continue,
+                Token::Id(_) => {}
+                Token::LBrace => return Err(invalid_data("subgraphs are not supported")),
+                other => return Err(invalid_data(format!("unexpected {:?}", other))),
+            }
+
+            if is_keyword(&statement, "subgraph") {
+                return Err(invalid_data("subgraphs are not supported"));
+            }
+
+            token = parser.next("}")?;
+
+            if is_keyword(&statement, "graph") || is_keyword(&statement, "node") || is_keyword(&statement, "edge") {
+                if token != Token::LBracket {
+                    return Err(invalid_data(format!("expected [ after {:?}", statement)));
+                }
+                parser.skip_attributes()?;
+                continue;
+            }
+
+            if token == Token::Equals {
+                match parser.next("value")? {
+                    Token::Id(_) => continue,
+                    other => return Err(invalid_data(format!("expected value, found {:?}", other))),
+                }
+            }
+
+            let mut u = match statement {
+                Token::Id(name) => graph.node_id(&mut ids, name),
+                _ => unreachable!(),
+            };
+
+            loop {
+                match token {
+                    Token::Arrow | Token::Line => {
+                        if (token == Token::Arrow) != directed {
+                            return Err(invalid_data("edge operator does not match graph type"));
+                        }
+                        let v = match parser.next("node")? {
+                            Token::Id(name) => graph.node_id(&mut ids, name),
+                            Token::LBrace => return Err(invalid_data("subgraphs are not supported")),
+                            other => return Err(invalid_data(format!("expected node, found {:?}", other))),
+                        };
+                        graph.edges.push(Edge::new(u, v));
+                        u = v;
+                        token = parser.next("}")?;
+                    }
+                    Token::LBracket => {
+                        parser.skip_attributes()?;
+                        break;
+                    }
+                    Token::Separator => break,
+                    Token::RBrace | Token::Id(_) => {
+                        parser.back();
+                        break;
+                    }
+                    other => return Err(invalid_data(format!("unexpected {:?}", other))),
+                }
+            }
+        }
+
+        if parser.pos != parser.tokens.len() {
+            return Err(invalid_data("unexpected input after closing brace"));
+        }
+
+        Ok(graph)
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn name(&self, node: Node) -> Option<&str> {
+        self.names.get(node).map(|n| &n[..])
+    }
+
+    pub fn node(&self, name: &str) -> Option<Node> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    fn node_id(&mut self, ids: &mut HashMap<String, Node>, name: String) -> Node {
+        if let Some(&id) = ids.get(&name) {
+            return id;
+        }
+
+        let id = self.names.len();
+        self.names.push(name.clone());
+        ids.insert(name, id);
+        id
+    }
+}
+
+impl Generator for DotGraph {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        if self.directed {
+            Box::new(self.edges.iter().cloned())
+        } else {
+            Box::new(self.edges.iter().flat_map(|e| {
+                let reverse = if e.u() != e.v() { Some(Edge::new(e.v(), e.u())) } else { None };
+                Some(*e).into_iter().chain(reverse)
+            }))
+        }
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.names.len())
+    }
+}
+
+fn is_keyword(token: &Token, keyword: &str) -> bool {
+    match *token {
+        Token::Id(ref id) => id.eq_ignore_ascii_case(keyword),
+        _ => false,
+    }
+}
+
+fn tokenize(input: &str) -> io::Result<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+    let mut line_start = true;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => {
+                line_start = true;
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            '#' if line_start => {
+                while chars.peek().is_some_and(|&c| c != '\n') {
+                    chars.next();
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.peek().is_some_and(|&c| c != '\n') {
+                    chars.next();
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if prev == '*' => break,
+                        Some(c) => prev = c,
+                        None => return Err(invalid_data("unterminated comment")),
+                    }
+                }
+            }
+            '{' => tokens.push(Token::LBrace),
+            '}' => tokens.push(Token::RBrace),
+            '[' => tokens.push(Token::LBracket),
+            ']' => tokens.push(Token::RBracket),
+            '=' => tokens.push(Token::Equals),
+            ';' | ',' => tokens.push(Token::Separator),
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                tokens.push(Token::Arrow);
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                chars.next();
+                tokens.push(Token::Line);
+            }
+            '"' => {
+                let mut id = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('"') => id.push('"'),
+                            Some('\\') => id.push('\\'),
+                            Some(c) => {
+                                id.push('\\');
+                                id.push(c);
+                            }
+                            None => return Err(invalid_data("unterminated string")),
+                        },
+                        Some(c) => id.push(c),
+                        None => return Err(invalid_data("unterminated string")),
+                    }
+                }
+                tokens.push(Token::Id(id));
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let mut id = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || c == '_' || c == '.' {
+                        id.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Id(id));
+            }
+            c => return Err(invalid_data(format!("unexpected character '{}'", c))),
+        }
+
+        line_start = false;
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use StaticGraph;
+    use Generator;
+    use Graph;
+    use Edge;
+
+    use representations::AdjacencyList;
+    use representations::EdgeList;
+    use algorithms::bipartite_coloring;
+    use formats::to_dot;
+    use formats::highlight_tree;
+    use formats::color_classes;
+    use formats::DotGraph;
+
+    #[test]
+    fn directed_output() {
+        let mut graph = EdgeList::new();
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 2);
+
+        assert_eq!(to_dot(&graph).to_string(), "digraph {\n  0;\n  1;\n  2;\n  0 -> 1;\n  1 -> 2;\n}\n");
+    }
+
+    #[test]
+    fn undirected_output() {
+        let mut graph = EdgeList::new();
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 0);
+        graph.add_edge(2, 1);
+
+        assert_eq!(to_dot(&graph).undirected().to_string(), "graph {\n  0;\n  1;\n  2;\n  0 -- 1;\n  2 -- 1;\n}\n");
+    }
+
+    #[test]
+    fn attributes() {
+        let mut graph = EdgeList::new();
+        graph.add_edge(0, 1);
+
+        let dot = to_dot(&graph)
+            .node_attributes(|v| vec![("label".to_string(), format!("node \"{}\"", v))])
+            .edge_attributes(|e| vec![("weight".to_string(), (e.u() + e.v()).to_string()), ("color".to_string(), "blue".to_string())])
+            .to_string();
+
+        assert!(dot.contains("  0 [label=\"node \\\"0\\\"\"];\n"));
+        assert!(dot.contains("  0 -> 1 [weight=\"1\", color=\"blue\"];\n"));
+    }
+
+    #[test]
+    fn highlight_bfs_tree() {
+        let mut graph = EdgeList::new();
+        graph.add_edge(0, 1);
+        graph.add_edge(0, 2);
+        graph.add_edge(1, 2);
+
+        let pred = graph.breadth_first_search(0);
+        let dot = to_dot(&graph).edge_attributes(highlight_tree(&pred)).to_string();
+
+        assert!(dot.contains("  0 -> 1 [color=\"red\", penwidth=\"2\"];\n"));
+        assert!(dot.contains("  0 -> 2 [color=\"red\", penwidth=\"2\"];\n"));
+        assert!(dot.contains("  1 -> 2;\n"));
+    }
+
+    #[test]
+    fn highlight_color_classes() {
+        let mut graph = EdgeList::new();
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 2);
+
+        let colors = bipartite_coloring(&graph).unwrap();
+        let dot = to_dot(&graph).node_attributes(color_classes(&colors)).to_string();
+
+        assert!(dot.contains("  0 [style=\"filled\", fillcolor=\"lightblue\"];\n"));
+        assert!(dot.contains("  1 [style=\"filled\", fillcolor=\"lightpink\"];\n"));
+        assert!(dot.contains("  2 [style=\"filled\", fillcolor=\"lightblue\"];\n"));
+    }
+
+    #[test]
+    fn parse_digraph() {
+        let input = "strict digraph G {\n  // comment\n  rankdir=LR;\n  node [shape=box];\n  a -> b -> c [color=red];\n  \"d e\"\n  c -> a; /* block */ b -> \"d e\"\n}\n";
+        let dot = DotGraph::parse(input).unwrap();
+
+        assert!(dot.is_directed());
+        assert_eq!(dot.num_nodes(), 4);
+        assert_eq!(dot.node("d e"), Some(3));
+        assert_eq!(dot.name(2), Some("c"));
+        assert_eq!(dot.edges().collect::<Vec<Edge>>(), vec![Edge::new(0, 1), Edge::new(1, 2), Edge::new(2, 0), Edge::new(1, 3)]);
+    }
+
+    #[test]
+    fn parse_undirected() {
+        let dot = DotGraph::parse("graph { 0 -- 1; 1 -- 2; 3 }").unwrap();
+        let graph = AdjacencyList::from_generator(&dot);
+
+        assert_eq!(graph.num_nodes(), 4);
+        assert_eq!(graph.num_edges(), 4);
+        assert!(graph.has_edge(2, 1));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut graph = AdjacencyList::new();
+        graph.add_edge(0, 3);
+        graph.add_edge(3, 1);
+        graph.add_edge(1, 1);
+        graph.add_edge(5, 0);
+
+        let dot = DotGraph::parse(&to_dot(&graph).to_string()).unwrap();
+
+        assert_eq!(dot.num_nodes(), graph.num_nodes());
+        assert_eq!(dot.edges().collect::<Vec<Edge>>(), graph.edges().collect::<Vec<Edge>>());
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert!(DotGraph::parse("").is_err());
+        assert!(DotGraph::parse("tree { a -> b }").is_err());
+        assert!(DotGraph::parse("digraph { a -- b }").is_err());
+        assert!(DotGraph::parse("digraph { a -> { b c } }").is_err());
+        assert!(DotGraph::parse("digraph { subgraph s { a } }").is_err());
+        assert!(DotGraph::parse("digraph { a -> b ").is_err());
+        assert!(DotGraph::parse("digraph { \"a -> b }").is_err());
+    }
+}
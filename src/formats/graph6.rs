@@ -0,0 +1,503 @@
+use Generator;
+use Node;
+use Edge;
+
+use super::invalid_data;
+
+use std::cmp;
+use std::collections::BTreeMap;
+use std::io;
+use std::io::BufRead;
+
+///
+/// The string encodings of [nauty](https://pallini.di.uniroma1.it/) as
+/// described in its `formats.txt`.
+///
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Graph6Format {
+    /// Simple undirected graphs. Loops and multi-edges are not representable.
+    Graph6,
+    /// Undirected graphs with loops and multi-edges, compact for sparse graphs.
+    Sparse6,
+    /// Directed graphs with loops.
+    Digraph6,
+}
+
+///
+/// Graph decoded from a graph6, sparse6 or digraph6 string. Edges of the
+/// undirected formats are generated in both directions.
+///
+pub struct Graph6 {
+    format: Graph6Format,
+    num_nodes: usize,
+    edges: Vec<Edge>,
+}
+
+impl Graph6 {
+    ///
+    /// Decodes a single graph. The format is detected from the leading
+    /// character, an optional `>>graph6<<` style header is skipped.
+    ///
+    pub fn decode(line: &str) -> io::Result<Graph6> {
+        let line = line.trim_end_matches(['\n', '\r']);
+        let line = [">>graph6<<", ">>sparse6<<", ">>digraph6<<"].iter()
+            .find(|h| line.starts_with(*h))
+            .map_or(line, |h| &line[h.len()..]);
+        let bytes = line.as_bytes();
+
+        let (format, data) = match bytes.first() {
+            Some(&b':') => (Graph6Format::Sparse6, &bytes[1..]),
+            Some(&b'&') => (Graph6Format::Digraph6, &bytes[1..]),
+            Some(_) => (Graph6Format::Graph6, bytes),
+            None => return Err(invalid_data("empty graph6 string")),
+        };
+
+        if let Some(&b) = data.iter().find(|&&b| !(63..=126).contains(&b)) {
+            return Err(invalid_data(format!("invalid graph6 character '{}'", b as char)));
+        }
+
+        let (num_nodes, data) = decode_size(data)?;
+        let mut bits = BitReader { data, pos: 0 };
+        let mut edges = vec![];
+
+        match format {
+            Graph6Format::Graph6 => {
+                let pairs = num_nodes.checked_mul(num_nodes.saturating_sub(1)).ok_or_else(|| invalid_data("graph6 size too large"))? / 2;
+                if data.len() != pairs.div_ceil(6) {
+                    return Err(invalid_data("graph6 string has wrong length"));
+                }
+                for v in 1..num_nodes {
+                    for u in 0..v {
+                        if bits.next() == Some(true) {
+                            edges.push(Edge::new(u, v));
+                        }
+                    }
+                }
+            }
+            Graph6Format::Digraph6 => {
+                let pairs = num_nodes.checked_mul(num_nodes).ok_or_else(|| invalid_data("digraph6 size too large"))?;
+                if data.len() != pairs.div_ceil(6) {
+                    return Err(invalid_data("digraph6 string has wrong length"));
+                }
+                for u in 0..num_nodes {
+                    for v in 0..num_nodes {
+                        if bits.next() == Some(true) {
+                            edges.push(Edge::new(u, v));
+                        }
+                    }
+                }
+            }
+            Graph6Format::Sparse6 => {
+                let k = bits_needed(num_nodes);
+                let mut v = 0;
+
+                while let (Some(b), Some(x)) = (bits.next(), bits.take(k)) {
+                    if b {
+                        v += 1;
+                    }
+                    // Padding with ones can produce out of range values.
+                    if x >= num_nodes || v >= num_nodes {
+                        break;
+                    } else if x > v {
+                        v = x;
+                    } else {
+                        edges.push(Edge::new(x, v));
+                    }
+                }
+            }
+        }
+
+        Ok(Graph6 { format, num_nodes, edges })
+    }
+
+    ///
+    /// Encodes the graph of **gen** without header. The number of nodes is
+    /// taken from `num_nodes_hint`, or else from the largest endpoint.
+    ///
+    /// For the undirected formats, the edges **(u, v)** and **(v, u)** form
+    /// one undirected edge. graph6 drops loops and multi-edges, sparse6 keeps
+    /// the larger multiplicity of both directions.
+    ///
+    /// Fails if the adjacency matrix of graph6 or digraph6 is too large to
+    /// address.
+    ///
+    pub fn encode<T: Generator>(gen: &T, format: Graph6Format) -> io::Result<String> {
+        let mut num_nodes = gen.num_nodes_hint().unwrap_or(0);
+        let mut counts = BTreeMap::new();
+
+        for e in gen.edges() {
+            num_nodes = cmp::max(num_nodes, cmp::max(e.u(), e.v()) + 1);
+            *counts.entry((e.u(), e.v())).or_insert(0) += 1;
+        }
+
+        let mut out = vec![];
+        let mut bits = BitWriter { out: vec![], current: 0, len: 0 };
+
+        match format {
+            Graph6Format::Graph6 => {
+                encode_size(num_nodes, &mut out);
+                let pairs = num_nodes.checked_mul(num_nodes.saturating_sub(1)).ok_or_else(|| invalid_data("graph6 size too large"))? / 2;
+                let mut matrix = vec![false; pairs];
+                for &(u, v) in counts.keys() {
+                    if u != v {
+                        let (u, v) = (cmp::min(u, v), cmp::max(u, v));
+                        matrix[v * (v - 1) / 2 + u] = true;
+                    }
+                }
+                for b in matrix {
+                    bits.push(b);
+                }
+                out.extend(bits.finish(false));
+            }
+            Graph6Format::Digraph6 => {
+                out.push(b'&');
+                encode_size(num_nodes, &mut out);
+                let pairs = num_nodes.checked_mul(num_nodes).ok_or_else(|| invalid_data("digraph6 size too large"))?;
+                let mut matrix = vec![false; pairs];
+                for &(u, v) in counts.keys() {
+                    matrix[u * num_nodes + v] = true;
+                }
+                for b in matrix {
+                    bits.push(b);
+                }
+                out.extend(bits.finish(false));
+            }
+            Graph6Format::Sparse6 => {
+                out.push(b':');
+                encode_size(num_nodes, &mut out);
+
+                // Undirected edges as (max, min) so that sorting orders by larger endpoint.
+                let mut undirected = BTreeMap::new();
+                for (&(u, v), &count) in &counts {
+                    let key = (cmp::max(u, v), cmp::min(u, v));
+                    let entry = undirected.entry(key).or_insert(0);
+                    *entry = cmp::max(*entry, count);
+                }
+
+                let k = bits_needed(num_nodes);
+                let mut current = 0;
+                for (&(v, u), &count) in &undirected {
+                    for _ in 0..count {
+                        if v == current {
+                            bits.push(false);
+                        } else if v == current + 1 {
+                            current = v;
+                            bits.push(true);
+                        } else {
+                            current = v;
+                            bits.push(true);
+                            bits.push_value(v, k);
+                            bits.push(false);
+                        }
+                        bits.push_value(u, k);
+                    }
+                }
+
+                let padding = (6 - bits.len % 6) % 6;
+                if k < 6 && num_nodes == 1 << k && padding >= k && current + 2 == num_nodes {
+                    // Node n - 2 is current, so padding with ones would read as an edge at node n - 1.
+                    bits.push(false);
+                }
+                out.extend(bits.finish(true));
+            }
+        }
+
+        Ok(String::from_utf8(out).expect("graph6 encoding is ASCII"))
+    }
+
+    pub fn format(&self) -> Graph6Format {
+        self.format
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.num_nodes
+    }
+}
+
+impl Generator for Graph6 {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        if self.format == Graph6Format::Digraph6 {
+            Box::new(self.edges.iter().cloned())
+        } else {
+            Box::new(self.edges.iter().flat_map(|e| {
+                let reverse = if e.u() != e.v() { Some(Edge::new(e.v(), e.u())) } else { None };
+                Some(*e).into_iter().chain(reverse)
+            }))
+        }
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.num_nodes)
+    }
+}
+
+///
+/// Streams the graphs of a corpus with one encoded graph per line. Empty
+/// lines are skipped.
+///
+pub struct Graph6Reader<R> {
+    lines: io::Lines<R>,
+}
+
+impl<R: BufRead> Graph6Reader<R> {
+    pub fn new(reader: R) -> Graph6Reader<R> {
+        Graph6Reader { lines: reader.lines() }
+    }
+}
+
+impl<R: BufRead> Iterator for Graph6Reader<R> {
+    type Item = io::Result<Graph6>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for line in &mut self.lines {
+            match line {
+                Ok(ref line) if line.trim().is_empty() => continue,
+                Ok(line) => return Some(Graph6::decode(line.trim())),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+
+        None
+    }
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn next(&mut self) -> Option<bool> {
+        let byte = *self.data.get(self.pos / 6)? - 63;
+        let bit = byte >> (5 - self.pos % 6) & 1 == 1;
+        self.pos += 1;
+        Some(bit)
+    }
+
+    fn take(&mut self, k: usize) -> Option<Node> {
+        let mut value = 0;
+        for _ in 0..k {
+            value = value << 1 | self.next()? as usize;
+        }
+        Some(value)
+    }
+}
+
+struct BitWriter {
+    out: Vec<u8>,
+    current: u8,
+    len: usize,
+}
+
+impl BitWriter {
+    fn push(&mut self, bit: bool) {
+        self.current = self.current << 1 | bit as u8;
+        self.len += 1;
+        if self.len.is_multiple_of(6) {
+            self.out.push(self.current + 63);
+            self.current = 0;
+        }
+    }
+
+    fn push_value(&mut self, value: usize, k: usize) {
+        for i in (0..k).rev() {
+            self.push(value >> i & 1 == 1);
+        }
+    }
+
+    fn finish(mut self, padding: bool) -> Vec<u8> {
+        while !self.len.is_multiple_of(6) {
+            self.push(padding);
+        }
+        self.out
+    }
+}
+
+fn bits_needed(num_nodes: usize) -> usize {
+    let mut k = 0;
+    while num_nodes > 1 && (num_nodes - 1) >> k > 0 {
+        k += 1;
+    }
+    k
+}
+
+fn encode_size(n: usize, out: &mut Vec<u8>) {
+    if n <= 62 {
+        out.push(n as u8 + 63);
+    } else if n <= 258_047 {
+        out.push(126);
+        for shift in &[12, 6, 0] {
+            out.push((n >> shift & 63) as u8 + 63);
+        }
+    } else {
+        out.push(126);
+        out.push(126);
+        for shift in &[30, 24, 18, 12, 6, 0] {
+            out.push((n >> shift & 63) as u8 + 63);
+        }
+    }
+}
+
+fn decode_size(data: &[u8]) -> io::Result<(usize, &[u8])> {
+    let value = |bytes: &[u8]| bytes.iter().fold(0, |n, &b| n << 6 | (b - 63) as usize);
+
+    match data {
+        [126, 126, rest @ ..] if rest.len() >= 6 => Ok((value(&rest[..6]), &rest[6..])),
+        [126, 126, ..] => Err(invalid_data("truncated graph6 size")),
+        [126, rest @ ..] if rest.len() >= 3 => Ok((value(&rest[..3]), &rest[3..])),
+        [126, ..] => Err(invalid_data("truncated graph6 size")),
+        [n, rest @ ..] => Ok(((n - 63) as usize, rest)),
+        [] => Err(invalid_data("missing graph6 size")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use StaticGraph;
+    use Generator;
+    use Graph;
+    use Edge;
+
+    use representations::AdjacencyList;
+    use representations::Csr;
+    use representations::EdgeList;
+    use generators::CompleteGraph;
+    use formats::Graph6;
+    use formats::Graph6Format;
+    use formats::Graph6Reader;
+
+    fn petersen() -> AdjacencyList {
+        let mut graph = AdjacencyList::new();
+        for i in 0..5 {
+            for &(u, v) in &[(i, (i + 1) % 5), (i, i + 5), (i + 5, (i + 2) % 5 + 5)] {
+                graph.add_edge(u, v);
+                graph.add_edge(v, u);
+            }
+        }
+        graph
+    }
+
+    #[test]
+    fn graph6_known_strings() {
+        // Examples from the nauty formats description.
+        let graph = Graph6::decode("DQc").unwrap();
+        assert_eq!(graph.num_nodes(), 5);
+        assert_eq!(graph.edges().filter(|e| e.u() < e.v()).collect::<Vec<Edge>>(),
+                   vec![Edge::new(0, 2), Edge::new(1, 3), Edge::new(0, 4), Edge::new(3, 4)]);
+
+        assert_eq!(Graph6::encode(&petersen(), Graph6Format::Graph6).unwrap(), "IheA@GUAo");
+    }
+
+    #[test]
+    fn sparse6_known_string() {
+        let graph = Graph6::decode(":Fa@x^").unwrap();
+        let edges = graph.edges().filter(|e| e.u() <= e.v()).collect::<Vec<Edge>>();
+
+        assert_eq!(graph.num_nodes(), 7);
+        assert_eq!(edges, vec![Edge::new(0, 1), Edge::new(0, 2), Edge::new(1, 2), Edge::new(5, 6)]);
+    }
+
+    #[test]
+    fn digraph6_known_string() {
+        let graph = Graph6::decode("&DI?AO?").unwrap();
+
+        assert_eq!(graph.format(), Graph6Format::Digraph6);
+        assert_eq!(graph.edges().collect::<Vec<Edge>>(), vec![Edge::new(0, 2), Edge::new(0, 4), Edge::new(3, 1), Edge::new(3, 4)]);
+    }
+
+    #[test]
+    fn roundtrip_all_formats() {
+        let graph = Csr::from_generator(&petersen());
+
+        for &format in &[Graph6Format::Graph6, Graph6Format::Sparse6, Graph6Format::Digraph6] {
+            let decoded = Csr::from_generator(&Graph6::decode(&Graph6::encode(&graph, format).unwrap()).unwrap());
+
+            assert_eq!(decoded.num_nodes(), 10);
+            assert_eq!(decoded.num_edges(), 30);
+            for e in graph.edges() {
+                assert!(decoded.has_edge(e.u(), e.v()));
+            }
+        }
+    }
+
+    #[test]
+    fn sparse6_loops_and_multi_edges() {
+        let mut graph = EdgeList::new();
+        graph.add_edge(0, 0);
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 0);
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 0);
+        graph.add_edge(3, 2);
+
+        let decoded = Graph6::decode(&Graph6::encode(&graph, Graph6Format::Sparse6).unwrap()).unwrap();
+        let mut edges = decoded.edges().collect::<Vec<Edge>>();
+        edges.sort();
+
+        assert_eq!(edges, vec![Edge::new(0, 0), Edge::new(0, 1), Edge::new(0, 1), Edge::new(1, 0), Edge::new(1, 0), Edge::new(2, 3), Edge::new(3, 2)]);
+    }
+
+    #[test]
+    fn sparse6_padding_special_case() {
+        // Edges {0, 2} and {1, 2} on four nodes, plain padding would add a loop at node 3.
+        let graph = Graph6::decode("CW").unwrap();
+        let decoded = Graph6::decode(&Graph6::encode(&graph, Graph6Format::Sparse6).unwrap()).unwrap();
+
+        assert_eq!(decoded.num_nodes(), 4);
+        assert_eq!(decoded.edges().collect::<Vec<Edge>>(), graph.edges().collect::<Vec<Edge>>());
+    }
+
+    #[test]
+    fn sparse6_padding_with_ones() {
+        // Edge {0, 1} on four nodes, node 2 has no edge so nauty pads with ones only.
+        let graph = Graph6::decode("C_").unwrap();
+
+        assert_eq!(Graph6::encode(&graph, Graph6Format::Sparse6).unwrap(), ":Cf");
+    }
+
+    #[test]
+    fn encode_overflow() {
+        let mut graph = EdgeList::new();
+        graph.add_edge(usize::MAX / 2, 0);
+
+        assert!(Graph6::encode(&graph, Graph6Format::Graph6).is_err());
+        assert!(Graph6::encode(&graph, Graph6Format::Digraph6).is_err());
+    }
+
+    #[test]
+    fn large_sizes() {
+        for &n in &[62, 63, 258_047, 258_048] {
+            let mut graph = EdgeList::new();
+            graph.add_edge(n - 2, n - 1);
+
+            let encoded = Graph6::encode(&graph, Graph6Format::Sparse6).unwrap();
+            let decoded = Graph6::decode(&encoded).unwrap();
+
+            assert_eq!(decoded.num_nodes(), n);
+            assert_eq!(decoded.edges().collect::<Vec<Edge>>(), vec![Edge::new(n - 2, n - 1), Edge::new(n - 1, n - 2)]);
+        }
+    }
+
+    #[test]
+    fn stream_corpus() {
+        let complete = CompleteGraph::new(4);
+        let corpus = format!(">>graph6<<{}\n\n{}\n:Fa@x^\n", Graph6::encode(&complete, Graph6Format::Graph6).unwrap(), Graph6::encode(&complete, Graph6Format::Digraph6).unwrap());
+        let graphs = Graph6Reader::new(corpus.as_bytes()).collect::<Result<Vec<Graph6>, _>>().unwrap();
+
+        assert_eq!(graphs.len(), 3);
+        assert_eq!(AdjacencyList::from_generator(&graphs[0]).num_edges(), 12);
+        assert_eq!(AdjacencyList::from_generator(&graphs[1]).num_edges(), 16);
+        assert_eq!(graphs[2].format(), Graph6Format::Sparse6);
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert!(Graph6::decode("").is_err());
+        assert!(Graph6::decode("D Qc").is_err());
+        assert!(Graph6::decode("DQ").is_err());
+        assert!(Graph6::decode("&DI?").is_err());
+        assert!(Graph6::decode("~?").is_err());
+        assert!(Graph6::decode("~~~~~~~~~~~~~~").is_err());
+        assert!(Graph6::decode("&~~~~~~~~").is_err());
+    }
+}
@@ -0,0 +1,487 @@
+use super::Attributes;
+use super::LabeledGraph;
+use super::invalid_data;
+
+use std::collections::HashMap;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+
+///
+/// Reads the first graph of a [GraphML](http://graphml.graphdrawing.org/)
+/// document. `<data>` values of nodes and edges become attributes named by
+/// the `attr.name` of their `<key>`, with key defaults filled in. Graph
+/// level data, ports and unknown elements are ignored. Nested graphs,
+/// hyperedges and edges whose `directed` flag contradicts the `edgedefault`
+/// are rejected.
+///
+pub fn read_graphml<R: BufRead>(mut reader: R) -> io::Result<LabeledGraph> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+
+    // Key id to its domain and attribute name.
+    let mut keys: HashMap<String, (String, String)> = HashMap::new();
+    let mut defaults: Vec<(String, String)> = vec![];
+    let mut directed = None;
+    let mut done = false;
+    let mut nodes: Vec<(String, Attributes)> = vec![];
+    let mut edges: Vec<(String, String, Attributes)> = vec![];
+
+    let mut stack: Vec<String> = vec![];
+    let mut default_key = None;
+    let mut key = None;
+    let mut text: Option<String> = None;
+
+    for event in XmlParser::new(&input) {
+        match event? {
+            XmlEvent::Start(name, attrs) => {
+                let in_graph = directed.is_some() && !done;
+                let in_element = in_graph && grandparent(&stack) == Some("graph");
+
+                match (&name[..], stack.last().map(|p| &p[..])) {
+                    ("key", Some("graphml")) => {
+                        let id = required(&attrs, "id")?;
+                        let domain = attribute(&attrs, "for").unwrap_or("all").to_string();
+                        let attr_name = attribute(&attrs, "attr.name").unwrap_or(&id).to_string();
+                        keys.insert(id.clone(), (domain, attr_name));
+                        default_key = Some(id);
+                        text = None;
+                    }
+                    ("default", Some("key")) if grandparent(&stack) == Some("graphml") => text = Some(String::new()),
+                    ("graph", _) if in_graph => return Err(invalid_data("nested graphs are not supported")),
+                    ("graph", Some("graphml")) if !done => {
+                        directed = match attribute(&attrs, "edgedefault") {
+                            Some("directed") => Some(true),
+                            Some("undirected") => Some(false),
+                            Some(other) => return Err(invalid_data(format!("invalid edgedefault '{}'", other))),
+                            None => return Err(invalid_data("missing edgedefault")),
+                        };
+                    }
+                    ("node", Some("graph")) if in_graph => nodes.push((required(&attrs, "id")?, vec![])),
+                    ("edge", Some("graph")) if in_graph => {
+                        match (attribute(&attrs, "directed"), directed) {
+                            (Some("true"), Some(false)) => return Err(invalid_data("directed edge in undirected graph")),
+                            (Some("false"), Some(true)) => return Err(invalid_data("undirected edge in directed graph")),
+                            _ => {}
+                        }
+                        edges.push((required(&attrs, "source")?, required(&attrs, "target")?, vec![]));
+                    }
+                    ("hyperedge", _) if in_graph => return Err(invalid_data("hyperedges are not supported")),
+                    ("data", Some("node")) | ("data", Some("edge")) if in_element => {
+                        key = Some(required(&attrs, "key")?);
+                        text = Some(String::new());
+                    }
+                    _ => {}
+                }
+
+                stack.push(name);
+            }
+            XmlEvent::End(name) => {
+                if stack.pop().as_ref() != Some(&name) {
+                    return Err(invalid_data(format!("unexpected closing tag '{}'", name)));
+                }
+
+                let in_graph = directed.is_some() && !done;
+                let in_element = in_graph && grandparent(&stack) == Some("graph");
+
+                match (&name[..], stack.last().map(|p| &p[..])) {
+                    ("key", Some("graphml")) => default_key = None,
+                    ("default", Some("key")) if grandparent(&stack) == Some("graphml") => {
+                        if let (Some(key), Some(text)) = (default_key.clone(), text.take()) {
+                            defaults.push((key, text));
+                        }
+                    }
+                    ("data", Some(parent)) if in_element && (parent == "node" || parent == "edge") => {
+                        if let (Some(key), Some(text)) = (key.take(), text.take()) {
+                            let attribute = (keys.get(&key).map_or(key, |k| k.1.clone()), text);
+                            let attributes = match parent {
+                                "node" => nodes.last_mut().map(|n| &mut n.1),
+                                _ => edges.last_mut().map(|e| &mut e.2),
+                            };
+                            attributes.ok_or_else(|| invalid_data(format!("data outside of a {}", parent)))?.push(attribute);
+                        }
+                    }
+                    ("graph", Some("graphml")) if in_graph => done = true,
+                    _ => {}
+                }
+            }
+            XmlEvent::Text(content) => {
+                if let Some(ref mut text) = text {
+                    text.push_str(&content);
+                }
+            }
+        }
+    }
+
+    if let Some(open) = stack.last() {
+        return Err(invalid_data(format!("unclosed element '{}'", open)));
+    }
+
+    let mut graph = LabeledGraph::empty(directed.ok_or_else(|| invalid_data("missing graph element"))?);
+
+    for (key, value) in defaults {
+        let (domain, name) = keys.get(&key).cloned().ok_or_else(|| invalid_data(format!("default for unknown key '{}'", key)))?;
+        let missing = |attributes: &Attributes| !attributes.iter().any(|a| a.0 == name);
+
+        if domain == "node" || domain == "all" {
+            for node in nodes.iter_mut().filter(|n| missing(&n.1)) {
+                node.1.push((name.clone(), value.clone()));
+            }
+        }
+        if domain == "edge" || domain == "all" {
+            for edge in edges.iter_mut().filter(|e| missing(&e.2)) {
+                edge.2.push((name.clone(), value.clone()));
+            }
+        }
+    }
+
+    for (id, attributes) in nodes {
+        graph.add_node(id, attributes)?;
+    }
+
+    for (source, target, attributes) in edges {
+        graph.add_edge(&source, &target, attributes)?;
+    }
+
+    Ok(graph)
+}
+
+///
+/// Writes **graph** as a GraphML document. Every attribute name becomes a
+/// `<key>` of type `string`.
+///
+pub fn write_graphml<W: Write>(graph: &LabeledGraph, writer: &mut W) -> io::Result<()> {
+    let mut node_keys: Vec<String> = vec![];
+    let mut edge_keys: Vec<String> = vec![];
+
+    for v in 0..graph.num_nodes() {
+        for (name, _) in graph.node_attributes(v).into_iter().flatten() {
+            if !node_keys.contains(name) {
+                node_keys.push(name.clone());
+            }
+        }
+    }
+    for (_, attributes) in graph.attributed_edges() {
+        for (name, _) in attributes {
+            if !edge_keys.contains(name) {
+                edge_keys.push(name.clone());
+            }
+        }
+    }
+
+    writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(writer, "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">")?;
+    for (i, name) in node_keys.iter().enumerate() {
+        writeln!(writer, "  <key id=\"n{}\" for=\"node\" attr.name=\"{}\" attr.type=\"string\"/>", i, escape(name))?;
+    }
+    for (i, name) in edge_keys.iter().enumerate() {
+        writeln!(writer, "  <key id=\"e{}\" for=\"edge\" attr.name=\"{}\" attr.type=\"string\"/>", i, escape(name))?;
+    }
+
+    let edgedefault = if graph.is_directed() { "directed" } else { "undirected" };
+    writeln!(writer, "  <graph edgedefault=\"{}\">", edgedefault)?;
+
+    for v in 0..graph.num_nodes() {
+        let id = escape(graph.name(v).unwrap_or_default());
+        let attributes = graph.node_attributes(v).map_or(&[][..], |a| &a[..]);
+        write_element(writer, &format!("node id=\"{}\"", id), "node", attributes, &node_keys, 'n')?;
+    }
+
+    for (e, attributes) in graph.attributed_edges() {
+        let source = escape(graph.name(e.u()).unwrap_or_default());
+        let target = escape(graph.name(e.v()).unwrap_or_default());
+        write_element(writer, &format!("edge source=\"{}\" target=\"{}\"", source, target), "edge", attributes, &edge_keys, 'e')?;
+    }
+
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")
+}
+
+fn write_element<W: Write>(writer: &mut W, open: &str, tag: &str, attributes: &[(String, String)], keys: &[String], prefix: char) -> io::Result<()> {
+    if attributes.is_empty() {
+        return writeln!(writer, "    <{}/>", open);
+    }
+
+    writeln!(writer, "    <{}>", open)?;
+    for (name, value) in attributes {
+        let key = keys.iter().position(|k| k == name).expect("key was collected");
+        writeln!(writer, "      <data key=\"{}{}\">{}</data>", prefix, key, escape(value))?;
+    }
+    writeln!(writer, "    </{}>", tag)
+}
+
+fn attribute<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs.iter().find(|a| a.0 == name).map(|a| &a.1[..])
+}
+
+fn required(attrs: &[(String, String)], name: &str) -> io::Result<String> {
+    attribute(attrs, name).map(|a| a.to_string()).ok_or_else(|| invalid_data(format!("missing attribute '{}'", name)))
+}
+
+///
+/// Name of the element two levels above the top of **stack**.
+///
+fn grandparent(stack: &[String]) -> Option<&str> {
+    stack.len().checked_sub(2).map(|i| &stack[i][..])
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn unescape(text: &str) -> io::Result<String> {
+    let mut out = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        let end = rest[start..].find(';').ok_or_else(|| invalid_data("unterminated entity"))? + start;
+        let entity = &rest[start + 1..end];
+        let c = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ if entity.starts_with("#x") => u32::from_str_radix(&entity[2..], 16).ok().and_then(::std::char::from_u32)
+                .ok_or_else(|| invalid_data(format!("invalid entity '{}'", entity)))?,
+            _ if entity.starts_with('#') => entity[1..].parse::<u32>().ok().and_then(::std::char::from_u32)
+                .ok_or_else(|| invalid_data(format!("invalid entity '{}'", entity)))?,
+            _ => return Err(invalid_data(format!("unknown entity '{}'", entity))),
+        };
+        out.push(c);
+        rest = &rest[end + 1..];
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+enum XmlEvent {
+    /// Local element name and attributes. Self-closing elements are followed
+    /// by a matching `End`.
+    Start(String, Vec<(String, String)>),
+    End(String),
+    Text(String),
+}
+
+///
+/// Minimal XML tokenizer, enough for GraphML. Skips the prolog, comments,
+/// processing instructions and doctype. Element namespace prefixes are
+/// dropped.
+///
+struct XmlParser<'a> {
+    rest: &'a str,
+    pending_end: Option<String>,
+}
+
+impl<'a> XmlParser<'a> {
+    fn new(input: &'a str) -> XmlParser<'a> {
+        XmlParser { rest: input, pending_end: None }
+    }
+
+    fn skip_past(&mut self, pattern: &str) -> io::Result<()> {
+        let end = self.rest.find(pattern).ok_or_else(|| invalid_data(format!("missing '{}'", pattern)))?;
+        self.rest = &self.rest[end + pattern.len()..];
+        Ok(())
+    }
+
+    fn tag(&mut self) -> io::Result<XmlEvent> {
+        let end = find_tag_end(self.rest).ok_or_else(|| invalid_data("unterminated tag"))?;
+        let tag = &self.rest[1..end];
+        self.rest = &self.rest[end + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            return Ok(XmlEvent::End(local_name(name.trim()).to_string()));
+        }
+
+        let (tag, empty) = match tag.strip_suffix('/') {
+            Some(tag) => (tag, true),
+            None => (tag, false),
+        };
+
+        let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+        let name = local_name(&tag[..name_end]).to_string();
+        let mut attrs = vec![];
+        let mut rest = tag[name_end..].trim_start();
+
+        while !rest.is_empty() {
+            let eq = rest.find('=').ok_or_else(|| invalid_data(format!("invalid attribute in <{}>", name)))?;
+            let attr = rest[..eq].trim().to_string();
+            rest = rest[eq + 1..].trim_start();
+
+            let quote = rest.chars().next().filter(|&c| c == '"' || c == '\'')
+                .ok_or_else(|| invalid_data(format!("unquoted attribute '{}'", attr)))?;
+            let close = rest[1..].find(quote).ok_or_else(|| invalid_data(format!("unterminated attribute '{}'", attr)))? + 1;
+            attrs.push((attr, unescape(&rest[1..close])?));
+            rest = rest[close + 1..].trim_start();
+        }
+
+        if empty {
+            self.pending_end = Some(name.clone());
+        }
+
+        Ok(XmlEvent::Start(name, attrs))
+    }
+}
+
+impl<'a> Iterator for XmlParser<'a> {
+    type Item = io::Result<XmlEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(name) = self.pending_end.take() {
+            return Some(Ok(XmlEvent::End(name)));
+        }
+
+        loop {
+            if self.rest.is_empty() {
+                return None;
+            }
+
+            let result = if self.rest.starts_with("<!--") {
+                self.skip_past("-->")
+            } else if self.rest.starts_with("<![CDATA[") {
+                let end = match self.rest.find("]]>") {
+                    Some(end) => end,
+                    None => return Some(Err(invalid_data("unterminated CDATA section"))),
+                };
+                let text = self.rest[9..end].to_string();
+                self.rest = &self.rest[end + 3..];
+                return Some(Ok(XmlEvent::Text(text)));
+            } else if self.rest.starts_with("<?") {
+                self.skip_past("?>")
+            } else if self.rest.starts_with("<!") {
+                self.skip_past(">")
+            } else if self.rest.starts_with('<') {
+                return Some(self.tag());
+            } else {
+                let end = self.rest.find('<').unwrap_or(self.rest.len());
+                let text = &self.rest[..end];
+                self.rest = &self.rest[end..];
+                return Some(unescape(text).map(XmlEvent::Text));
+            };
+
+            if let Err(err) = result {
+                return Some(Err(err));
+            }
+        }
+    }
+}
+
+fn find_tag_end(input: &str) -> Option<usize> {
+    let mut quote = None;
+
+    for (i, c) in input.char_indices() {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use StaticGraph;
+    use Generator;
+    use Graph;
+    use Edge;
+
+    use representations::AdjacencyList;
+    use representations::Csr;
+    use formats::LabeledGraph;
+    use formats::read_graphml;
+    use formats::write_graphml;
+
+    const INPUT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- example -->
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns"
+         xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+  <key id="d0" for="node" attr.name="color" attr.type="string">
+    <default>yellow</default>
+  </key>
+  <key id="d1" for="edge" attr.name="weight" attr.type="double"/>
+  <graph id="G" edgedefault="undirected">
+    <data key="d2">ignored</data>
+    <node id="n0">
+      <data key="d0">green</data>
+    </node>
+    <node id="n1"/>
+    <node id="n2"><data key="d0">&lt;blue&gt;</data></node>
+    <edge source="n0" target="n2"><data key="d1">1.5</data></edge>
+    <edge id="e1" source="n1" target="n2"/>
+  </graph>
+</graphml>
+"#;
+
+    #[test]
+    fn read() {
+        let graph = read_graphml(INPUT.as_bytes()).unwrap();
+
+        assert!(!graph.is_directed());
+        assert_eq!(graph.num_nodes(), 3);
+        assert_eq!(graph.node("n2"), Some(2));
+        assert_eq!(graph.node_attributes(0).unwrap(), &vec![("color".to_string(), "green".to_string())]);
+        assert_eq!(graph.node_attributes(1).unwrap(), &vec![("color".to_string(), "yellow".to_string())]);
+        assert_eq!(graph.node_attributes(2).unwrap(), &vec![("color".to_string(), "<blue>".to_string())]);
+
+        let edges = graph.attributed_edges().collect::<Vec<_>>();
+        assert_eq!(edges[0], (Edge::new(0, 2), &vec![("weight".to_string(), "1.5".to_string())]));
+        assert_eq!(edges[1], (Edge::new(1, 2), &vec![]));
+
+        let csr = Csr::from_generator(&graph);
+        assert_eq!(csr.num_edges(), 4);
+        assert!(csr.has_edge(2, 0));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut al = AdjacencyList::new();
+        al.add_edge(0, 1);
+        al.add_edge(1, 2);
+        al.add_edge(3, 0);
+
+        let graph = LabeledGraph::new(&al, true)
+            .with_node_attributes(|v| vec![("label".to_string(), format!("\"node\" & {}", v))])
+            .with_edge_attributes(|e| if e.u() == 1 { vec![("w".to_string(), "2".to_string())] } else { vec![] });
+
+        let mut buf = vec![];
+        write_graphml(&graph, &mut buf).unwrap();
+        let read = read_graphml(&buf[..]).unwrap();
+
+        assert!(read.is_directed());
+        assert_eq!(read.num_nodes(), 4);
+        assert_eq!(read.edges().collect::<Vec<Edge>>(), al.edges().collect::<Vec<Edge>>());
+        assert_eq!(read.node_attributes(3).unwrap(), &vec![("label".to_string(), "\"node\" & 3".to_string())]);
+        assert_eq!(read.attributed_edges().map(|(_, a)| a.clone()).collect::<Vec<_>>(),
+                   vec![vec![], vec![("w".to_string(), "2".to_string())], vec![]]);
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert!(read_graphml("<graphml></graphml>".as_bytes()).is_err());
+        assert!(read_graphml("<graphml><graph edgedefault=\"directed\"><node id=\"a\"/><node id=\"a\"/></graph></graphml>".as_bytes()).is_err());
+        assert!(read_graphml("<graphml><graph edgedefault=\"directed\"><edge source=\"a\" target=\"b\"/></graph></graphml>".as_bytes()).is_err());
+        assert!(read_graphml("<graphml><graph edgedefault=\"directed\"><node id=\"a\"><graph edgedefault=\"directed\"/></node></graph></graphml>".as_bytes()).is_err());
+        assert!(read_graphml("<graphml><graph edgedefault=\"directed\"><node id=\"a\"/></graphml>".as_bytes()).is_err());
+        assert!(read_graphml("<graphml><graph edgedefault=\"undirected\"><node id=\"a\"/><edge directed=\"true\" source=\"a\" target=\"a\"/></graph></graphml>".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn misplaced_elements() {
+        let nested_key = r#"<graphml><graph edgedefault="directed"><node id="a"><data key="zz"><key><default>x</default></key></data></node></graph></graphml>"#;
+        let graph = read_graphml(nested_key.as_bytes()).unwrap();
+        assert_eq!(graph.num_nodes(), 1);
+        assert_eq!(graph.node_attributes(0).unwrap(), &vec![("zz".to_string(), "x".to_string())]);
+
+        let nested_node = r#"<graphml><graph edgedefault="directed"><foo><node id="a"><data key="d">x</data></node></foo></graph></graphml>"#;
+        assert_eq!(read_graphml(nested_node.as_bytes()).unwrap().num_nodes(), 0);
+    }
+}
@@ -0,0 +1,132 @@
+use Generator;
+use StaticGraph;
+use Node;
+use Edge;
+
+use super::Attributes;
+use super::invalid_data;
+
+use std::collections::HashMap;
+use std::io;
+
+///
+/// Graph with named nodes and string attributes on nodes and edges, as read
+/// from and written to GraphML and JSON node-link data.
+///
+/// Node names are mapped to dense ids in order of declaration. An undirected
+/// graph stores each edge once and generates it in both directions.
+///
+pub struct LabeledGraph {
+    directed: bool,
+    names: Vec<String>,
+    ids: HashMap<String, Node>,
+    node_attributes: Vec<Attributes>,
+    edges: Vec<Edge>,
+    edge_attributes: Vec<Attributes>,
+}
+
+impl LabeledGraph {
+    ///
+    /// Takes the nodes and edges of **graph**, naming each node by its id.
+    /// In an undirected graph an edge present in both directions is stored
+    /// once.
+    ///
+    pub fn new<T: StaticGraph>(graph: &T, directed: bool) -> LabeledGraph {
+        let mut labeled = LabeledGraph::empty(directed);
+
+        for v in 0..graph.num_nodes() {
+            labeled.names.push(v.to_string());
+            labeled.ids.insert(v.to_string(), v);
+            labeled.node_attributes.push(vec![]);
+        }
+
+        for e in graph.edges() {
+            if directed || e.u() <= e.v() || !graph.has_edge(e.v(), e.u()) {
+                labeled.edges.push(e);
+                labeled.edge_attributes.push(vec![]);
+            }
+        }
+
+        labeled
+    }
+
+    pub fn with_node_attributes<F: Fn(Node) -> Attributes>(mut self, f: F) -> LabeledGraph {
+        self.node_attributes = (0..self.names.len()).map(f).collect();
+        self
+    }
+
+    pub fn with_edge_attributes<F: Fn(Edge) -> Attributes>(mut self, f: F) -> LabeledGraph {
+        self.edge_attributes = self.edges.iter().map(|&e| f(e)).collect();
+        self
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn name(&self, node: Node) -> Option<&str> {
+        self.names.get(node).map(|n| &n[..])
+    }
+
+    pub fn node(&self, name: &str) -> Option<Node> {
+        self.ids.get(name).cloned()
+    }
+
+    pub fn node_attributes(&self, node: Node) -> Option<&Attributes> {
+        self.node_attributes.get(node)
+    }
+
+    ///
+    /// The stored edges with their attributes. Unlike `edges()`, an
+    /// undirected edge is only listed once.
+    ///
+    pub fn attributed_edges<'a>(&'a self) -> Box<dyn Iterator<Item=(Edge, &'a Attributes)> + 'a> {
+        Box::new(self.edges.iter().cloned().zip(self.edge_attributes.iter()))
+    }
+
+    pub(super) fn empty(directed: bool) -> LabeledGraph {
+        LabeledGraph { directed, names: vec![], ids: HashMap::new(), node_attributes: vec![], edges: vec![], edge_attributes: vec![] }
+    }
+
+    pub(super) fn add_node(&mut self, name: String, attributes: Attributes) -> io::Result<Node> {
+        if self.ids.contains_key(&name) {
+            return Err(invalid_data(format!("duplicate node id '{}'", name)));
+        }
+
+        let id = self.names.len();
+        self.ids.insert(name.clone(), id);
+        self.names.push(name);
+        self.node_attributes.push(attributes);
+        Ok(id)
+    }
+
+    pub(super) fn add_edge(&mut self, source: &str, target: &str, attributes: Attributes) -> io::Result<()> {
+        let u = self.node(source).ok_or_else(|| invalid_data(format!("unknown node id '{}'", source)))?;
+        let v = self.node(target).ok_or_else(|| invalid_data(format!("unknown node id '{}'", target)))?;
+
+        self.edges.push(Edge::new(u, v));
+        self.edge_attributes.push(attributes);
+        Ok(())
+    }
+}
+
+impl Generator for LabeledGraph {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        if self.directed {
+            Box::new(self.edges.iter().cloned())
+        } else {
+            Box::new(self.edges.iter().flat_map(|e| {
+                let reverse = if e.u() != e.v() { Some(Edge::new(e.v(), e.u())) } else { None };
+                Some(*e).into_iter().chain(reverse)
+            }))
+        }
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.names.len())
+    }
+}
@@ -0,0 +1,271 @@
+use Generator;
+use StaticGraph;
+use Node;
+use Edge;
+
+use representations::Csr;
+
+use super::invalid_data;
+
+use std::cmp;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+
+///
+/// Graph read from a [Matrix Market](https://math.nist.gov/MatrixMarket/formats.html)
+/// coordinate file. Entry **(i, j)** becomes the edge **i - 1 → j - 1**.
+/// Symmetric matrices only store one triangle, so every off-diagonal entry
+/// is expanded to both directions.
+///
+/// The number of nodes is taken from the size line, which keeps isolated
+/// trailing nodes when converting into a `Csr`.
+///
+pub struct MatrixMarket {
+    num_nodes: usize,
+    edges: Vec<Edge>,
+    values: Option<Vec<f64>>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Field {
+    Pattern,
+    Real,
+    Integer,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Symmetry {
+    General,
+    Symmetric,
+}
+
+impl MatrixMarket {
+    pub fn read<R: BufRead>(reader: R) -> io::Result<MatrixMarket> {
+        let mut lines = reader.lines();
+
+        let header = match lines.next() {
+            Some(line) => line?,
+            None => return Err(invalid_data("empty matrix market file")),
+        };
+        let (field, symmetry) = parse_header(&header)?;
+
+        let mut size = None;
+        let mut edges = vec![];
+        let mut values = vec![];
+        let mut entries = 0;
+
+        for line in lines {
+            let line = line?;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('%') {
+                continue;
+            }
+
+            let tokens = line.split_whitespace().collect::<Vec<&str>>();
+
+            let (rows, cols, nnz) = match size {
+                Some(size) => size,
+                None => {
+                    if tokens.len() != 3 {
+                        return Err(invalid_data(format!("invalid size line '{}'", line)));
+                    }
+                    size = Some((parse_usize(tokens[0])?, parse_usize(tokens[1])?, parse_usize(tokens[2])?));
+                    continue;
+                }
+            };
+
+            let expected = if field == Field::Pattern { 2 } else { 3 };
+            if tokens.len() != expected {
+                return Err(invalid_data(format!("invalid entry '{}'", line)));
+            }
+
+            let i = parse_usize(tokens[0])?;
+            let j = parse_usize(tokens[1])?;
+            if i == 0 || j == 0 || i > rows || j > cols {
+                return Err(invalid_data(format!("entry ({}, {}) out of bounds", i, j)));
+            }
+
+            let value = match field {
+                Field::Pattern => 1.0,
+                Field::Real => tokens[2].parse::<f64>().map_err(|_| invalid_data(format!("invalid value '{}'", tokens[2])))?,
+                Field::Integer => tokens[2].parse::<i64>().map_err(|_| invalid_data(format!("invalid value '{}'", tokens[2])))? as f64,
+            };
+
+            entries += 1;
+            if entries > nnz {
+                return Err(invalid_data(format!("more than {} entries", nnz)));
+            }
+
+            edges.push(Edge::new(i - 1, j - 1));
+            values.push(value);
+
+            if symmetry == Symmetry::Symmetric && i != j {
+                edges.push(Edge::new(j - 1, i - 1));
+                values.push(value);
+            }
+        }
+
+        let (rows, cols, nnz) = match size {
+            Some(size) => size,
+            None => return Err(invalid_data("missing size line")),
+        };
+
+        if entries != nnz {
+            return Err(invalid_data(format!("expected {} entries, found {}", nnz, entries)));
+        }
+
+        let values = if field == Field::Pattern { None } else { Some(values) };
+
+        Ok(MatrixMarket { num_nodes: cmp::max(rows, cols), edges, values })
+    }
+
+    ///
+    /// Writes **graph** as a `pattern general` coordinate matrix of size
+    /// **n × n**.
+    ///
+    pub fn write<W: Write>(graph: &Csr, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "%%MatrixMarket matrix coordinate pattern general")?;
+        writeln!(writer, "{} {} {}", graph.num_nodes(), graph.num_nodes(), graph.num_edges())?;
+
+        for e in graph.edges() {
+            writeln!(writer, "{} {}", e.u() + 1, e.v() + 1)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.num_nodes
+    }
+
+    ///
+    /// Entry values in the same order as `edges()`, or `None` for `pattern`
+    /// matrices. Integer entries are converted to `f64`.
+    ///
+    pub fn values(&self) -> Option<&[f64]> {
+        self.values.as_ref().map(|v| &v[..])
+    }
+}
+
+impl Generator for MatrixMarket {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        Box::new(self.edges.iter().cloned())
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.num_nodes)
+    }
+}
+
+fn parse_header(header: &str) -> io::Result<(Field, Symmetry)> {
+    let tokens = header.split_whitespace().map(|t| t.to_lowercase()).collect::<Vec<String>>();
+
+    if tokens.len() != 5 || tokens[0] != "%%matrixmarket" || tokens[1] != "matrix" {
+        return Err(invalid_data(format!("invalid header '{}'", header)));
+    }
+
+    if tokens[2] != "coordinate" {
+        return Err(invalid_data(format!("unsupported format '{}'", tokens[2])));
+    }
+
+    let field = match &tokens[3][..] {
+        "pattern" => Field::Pattern,
+        "real" => Field::Real,
+        "integer" => Field::Integer,
+        other => return Err(invalid_data(format!("unsupported field '{}'", other))),
+    };
+
+    let symmetry = match &tokens[4][..] {
+        "general" => Symmetry::General,
+        "symmetric" => Symmetry::Symmetric,
+        other => return Err(invalid_data(format!("unsupported symmetry '{}'", other))),
+    };
+
+    Ok((field, symmetry))
+}
+
+fn parse_usize(token: &str) -> io::Result<Node> {
+    token.parse::<usize>().map_err(|_| invalid_data(format!("invalid index '{}'", token)))
+}
+
+#[cfg(test)]
+mod tests {
+    use StaticGraph;
+    use Generator;
+    use Graph;
+    use Edge;
+
+    use representations::Csr;
+    use representations::EdgeList;
+    use formats::MatrixMarket;
+
+    #[test]
+    fn pattern_general() {
+        let input = "%%MatrixMarket matrix coordinate pattern general\n% comment\n3 3 2\n1 2\n3 1\n";
+        let mm = MatrixMarket::read(input.as_bytes()).unwrap();
+
+        assert_eq!(mm.num_nodes(), 3);
+        assert_eq!(mm.edges().collect::<Vec<Edge>>(), vec![Edge::new(0, 1), Edge::new(2, 0)]);
+        assert!(mm.values().is_none());
+    }
+
+    #[test]
+    fn symmetric_expansion() {
+        let input = "%%MatrixMarket matrix coordinate real symmetric\n3 3 3\n1 1 2.0\n2 1 0.5\n3 2 -1\n";
+        let mm = MatrixMarket::read(input.as_bytes()).unwrap();
+        let graph = Csr::from_generator(&mm);
+
+        assert_eq!(graph.num_edges(), 5);
+        assert!(graph.has_edge(0, 0));
+        assert!(graph.has_edge(0, 1));
+        assert!(graph.has_edge(1, 0));
+        assert!(graph.has_edge(1, 2));
+        assert!(graph.has_edge(2, 1));
+        assert_eq!(mm.values().unwrap(), &[2.0, 0.5, 0.5, -1.0, -1.0]);
+    }
+
+    #[test]
+    fn integer_values() {
+        let input = "%%MatrixMarket matrix coordinate integer general\n2 2 1\n1 2 7\n";
+        let mm = MatrixMarket::read(input.as_bytes()).unwrap();
+
+        assert_eq!(mm.values().unwrap(), &[7.0]);
+    }
+
+    #[test]
+    fn isolated_trailing_nodes() {
+        let input = "%%MatrixMarket matrix coordinate pattern general\n10 10 1\n1 2\n";
+        let graph = Csr::from_generator(&MatrixMarket::read(input.as_bytes()).unwrap());
+
+        assert_eq!(graph.num_nodes(), 10);
+        assert_eq!(graph.num_edges(), 1);
+        assert_eq!(graph.neighbors(9).count(), 0);
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut el = EdgeList::new();
+        el.add_edge(0, 3);
+        el.add_edge(3, 1);
+        el.add_edge(1, 1);
+        let graph = Csr::from_generator(&el);
+
+        let mut buf = vec![];
+        MatrixMarket::write(&graph, &mut buf).unwrap();
+        let read = Csr::from_generator(&MatrixMarket::read(&buf[..]).unwrap());
+
+        assert_eq!(read.num_nodes(), graph.num_nodes());
+        assert_eq!(read.edges().collect::<Vec<Edge>>(), graph.edges().collect::<Vec<Edge>>());
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert!(MatrixMarket::read("".as_bytes()).is_err());
+        assert!(MatrixMarket::read("%%MatrixMarket matrix array real general\n2 2\n".as_bytes()).is_err());
+        assert!(MatrixMarket::read("%%MatrixMarket matrix coordinate complex general\n".as_bytes()).is_err());
+        assert!(MatrixMarket::read("%%MatrixMarket matrix coordinate pattern general\n2 2 1\n3 1\n".as_bytes()).is_err());
+        assert!(MatrixMarket::read("%%MatrixMarket matrix coordinate pattern general\n2 2 2\n1 1\n".as_bytes()).is_err());
+    }
+}
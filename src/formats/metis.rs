@@ -0,0 +1,363 @@
+use Generator;
+use StaticGraph;
+use Node;
+use Edge;
+
+use representations::Csr;
+
+use super::invalid_data;
+
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+
+///
+/// Undirected graph in the `.graph` format of the
+/// [METIS](https://github.com/KarypisLab/METIS) partitioner.
+///
+/// The header holds **n**, the number of undirected edges **m** and an
+/// optional `fmt` flag announcing vertex sizes, vertex weights and edge
+/// weights, followed by **ncon**, the number of weights per vertex. Line
+/// **i** then lists the 1-based neighbors of node **i - 1**, so the file maps
+/// directly onto the rows of a `Csr`.
+///
+/// Every edge must appear in both directions with the same weight. Loops are
+/// not allowed.
+///
+pub struct Metis {
+    graph: Csr,
+    vertex_sizes: Option<Vec<i64>>,
+    vertex_weights: Option<Vec<i64>>,
+    num_constraints: usize,
+    edge_weights: Option<Vec<i64>>,
+}
+
+impl Metis {
+    pub fn new(graph: Csr) -> Metis {
+        Metis { graph, vertex_sizes: None, vertex_weights: None, num_constraints: 0, edge_weights: None }
+    }
+
+    pub fn with_vertex_sizes(mut self, sizes: Vec<i64>) -> Metis {
+        assert_eq!(sizes.len(), self.graph.num_nodes(), "one size per node required");
+        self.vertex_sizes = Some(sizes);
+        self
+    }
+
+    ///
+    /// Attaches **ncon** weights per node, stored node by node.
+    ///
+    pub fn with_vertex_weights(mut self, weights: Vec<i64>, ncon: usize) -> Metis {
+        assert!(ncon > 0, "at least one weight per node required");
+        assert_eq!(weights.len(), self.graph.num_nodes() * ncon, "ncon weights per node required");
+        self.vertex_weights = Some(weights);
+        self.num_constraints = ncon;
+        self
+    }
+
+    ///
+    /// Attaches edge weights in the order of the `Csr` columns.
+    ///
+    pub fn with_edge_weights(mut self, weights: Vec<i64>) -> Metis {
+        assert_eq!(weights.len(), self.graph.num_edges(), "one weight per edge required");
+        self.edge_weights = Some(weights);
+        self
+    }
+
+    pub fn read<R: BufRead>(reader: R) -> io::Result<Metis> {
+        let mut lines = reader.lines();
+
+        let header = loop {
+            match lines.next() {
+                Some(line) => {
+                    let line = line?;
+                    if !line.trim().is_empty() && !line.starts_with('%') {
+                        break line;
+                    }
+                }
+                None => return Err(invalid_data("missing METIS header")),
+            }
+        };
+
+        let header = header.split_whitespace().map(parse_number).collect::<io::Result<Vec<usize>>>()?;
+        if header.len() < 2 || header.len() > 4 {
+            return Err(invalid_data("METIS header needs n, m and an optional fmt and ncon"));
+        }
+
+        let (num_nodes, num_edges) = (header[0], header[1]);
+        let fmt = header.get(2).cloned().unwrap_or(0);
+        if fmt % 10 > 1 || fmt / 10 % 10 > 1 || fmt / 100 > 1 {
+            return Err(invalid_data(format!("invalid fmt {}", fmt)));
+        }
+        let has_sizes = fmt / 100 == 1;
+        let has_vertex_weights = fmt / 10 % 10 == 1;
+        let has_edge_weights = fmt % 10 == 1;
+        let ncon = if has_vertex_weights { header.get(3).cloned().unwrap_or(1) } else { 0 };
+
+        // The sizes come from the header, so the arrays grow as lines are read
+        let mut row = vec![];
+        let mut col = vec![];
+        let mut vertex_sizes = vec![];
+        let mut vertex_weights = vec![];
+        let mut edge_weights = vec![];
+
+        row.push(0);
+
+        for line in lines {
+            let line = line?;
+            if line.starts_with('%') {
+                continue;
+            }
+
+            if row.len() > num_nodes {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                return Err(invalid_data(format!("more than {} vertex lines", num_nodes)));
+            }
+
+            let u = row.len() - 1;
+            let mut tokens = line.split_whitespace();
+
+            if has_sizes {
+                vertex_sizes.push(parse_weight(tokens.next())?);
+            }
+            for _ in 0..ncon {
+                vertex_weights.push(parse_weight(tokens.next())?);
+            }
+
+            while let Some(token) = tokens.next() {
+                let v = parse_number(token)?;
+                if v == 0 || v > num_nodes {
+                    return Err(invalid_data(format!("neighbor {} of node {} out of range", v, u + 1)));
+                }
+                if v - 1 == u {
+                    return Err(invalid_data(format!("loop at node {}", v)));
+                }
+                col.push(v - 1);
+
+                if has_edge_weights {
+                    edge_weights.push(parse_weight(tokens.next())?);
+                }
+            }
+
+            row.push(col.len());
+        }
+
+        if row.len() - 1 != num_nodes {
+            return Err(invalid_data(format!("expected {} vertex lines, found {}", num_nodes, row.len() - 1)));
+        }
+
+        if num_edges.checked_mul(2) != Some(col.len()) {
+            return Err(invalid_data(format!("expected {} edges, found {} adjacency entries", num_edges, col.len())));
+        }
+
+        let edge_weights = if has_edge_weights { Some(edge_weights) } else { None };
+        check_symmetric(&row, &col, edge_weights.as_ref())?;
+
+        Ok(Metis {
+            graph: Csr::from_raw_parts(row, col),
+            vertex_sizes: if has_sizes { Some(vertex_sizes) } else { None },
+            vertex_weights: if has_vertex_weights { Some(vertex_weights) } else { None },
+            num_constraints: ncon,
+            edge_weights,
+        })
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let row = self.graph.row();
+        let col = self.graph.col();
+
+        if (0..self.graph.num_nodes()).any(|u| self.graph.has_edge(u, u)) {
+            return Err(invalid_data("METIS graphs cannot have loops"));
+        }
+        check_symmetric(row, col, self.edge_weights.as_ref())?;
+
+        let fmt = (self.vertex_sizes.is_some() as u8, self.vertex_weights.is_some() as u8, self.edge_weights.is_some() as u8);
+
+        write!(writer, "{} {}", self.graph.num_nodes(), col.len() / 2)?;
+        if fmt != (0, 0, 0) {
+            write!(writer, " {}{}{}", fmt.0, fmt.1, fmt.2)?;
+            if self.num_constraints > 1 {
+                write!(writer, " {}", self.num_constraints)?;
+            }
+        }
+        writeln!(writer)?;
+
+        for u in 0..self.graph.num_nodes() {
+            let mut tokens = vec![];
+
+            if let Some(ref sizes) = self.vertex_sizes {
+                tokens.push(sizes[u].to_string());
+            }
+            if let Some(ref weights) = self.vertex_weights {
+                let ncon = self.num_constraints;
+                tokens.extend(weights[u * ncon..(u + 1) * ncon].iter().map(|w| w.to_string()));
+            }
+            for i in row[u]..row[u + 1] {
+                tokens.push((col[i] + 1).to_string());
+                if let Some(ref weights) = self.edge_weights {
+                    tokens.push(weights[i].to_string());
+                }
+            }
+
+            writeln!(writer, "{}", tokens.join(" "))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn graph(&self) -> &Csr {
+        &self.graph
+    }
+
+    pub fn into_graph(self) -> Csr {
+        self.graph
+    }
+
+    pub fn vertex_sizes(&self) -> Option<&[i64]> {
+        self.vertex_sizes.as_ref().map(|s| &s[..])
+    }
+
+    ///
+    /// The `num_constraints` weights of each node, stored node by node.
+    ///
+    pub fn vertex_weights(&self) -> Option<&[i64]> {
+        self.vertex_weights.as_ref().map(|w| &w[..])
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.num_constraints
+    }
+
+    ///
+    /// Edge weights in the order of the `Csr` columns.
+    ///
+    pub fn edge_weights(&self) -> Option<&[i64]> {
+        self.edge_weights.as_ref().map(|w| &w[..])
+    }
+}
+
+impl Generator for Metis {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        self.graph.edges()
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.graph.num_nodes())
+    }
+}
+
+fn check_symmetric(row: &[usize], col: &[Node], weights: Option<&Vec<i64>>) -> io::Result<()> {
+    let mut forward = vec![];
+    let mut backward = vec![];
+
+    for u in 0..row.len().saturating_sub(1) {
+        for i in row[u]..row[u + 1] {
+            let w = weights.map_or(0, |w| w[i]);
+            forward.push((u, col[i], w));
+            backward.push((col[i], u, w));
+        }
+    }
+
+    forward.sort();
+    backward.sort();
+
+    match forward.iter().zip(&backward).find(|&(f, b)| f != b) {
+        Some((&(u, v, _), _)) => Err(invalid_data(format!("edge {} {} is not matched by its reverse", u + 1, v + 1))),
+        None => Ok(()),
+    }
+}
+
+fn parse_number(token: &str) -> io::Result<usize> {
+    token.parse::<usize>().map_err(|_| invalid_data(format!("invalid number '{}'", token)))
+}
+
+fn parse_weight(token: Option<&str>) -> io::Result<i64> {
+    let token = token.ok_or_else(|| invalid_data("missing weight"))?;
+    token.parse::<i64>().map_err(|_| invalid_data(format!("invalid weight '{}'", token)))
+}
+
+#[cfg(test)]
+mod tests {
+    use StaticGraph;
+    use Generator;
+    use Graph;
+    use Edge;
+
+    use representations::Csr;
+    use representations::EdgeList;
+    use formats::Metis;
+
+    #[test]
+    fn unweighted() {
+        let input = "% triangle with a pendant node and an isolated node\n5 4\n2 3\n1 3\n1 2 4\n3\n\n";
+        let metis = Metis::read(input.as_bytes()).unwrap();
+        let graph = metis.graph();
+
+        assert_eq!(graph.num_nodes(), 5);
+        assert_eq!(graph.num_edges(), 8);
+        assert_eq!(graph.row(), &[0, 2, 4, 7, 8, 8]);
+        assert_eq!(graph.col(), &[1, 2, 0, 2, 0, 1, 3, 2]);
+        assert!(metis.edge_weights().is_none());
+    }
+
+    #[test]
+    fn weighted() {
+        let input = "3 2 111 2\n5 1 2 2 7\n4 2 0 1 7 3 1\n3 3 3 2 1\n";
+        let metis = Metis::read(input.as_bytes()).unwrap();
+
+        assert_eq!(metis.vertex_sizes().unwrap(), &[5, 4, 3]);
+        assert_eq!(metis.num_constraints(), 2);
+        assert_eq!(metis.vertex_weights().unwrap(), &[1, 2, 2, 0, 3, 3]);
+        assert_eq!(metis.edge_weights().unwrap(), &[7, 7, 1, 1]);
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut el = EdgeList::new();
+        for &(u, v) in &[(0, 1), (1, 2), (2, 0), (2, 4)] {
+            el.add_edge(u, v);
+            el.add_edge(v, u);
+        }
+        let graph = Csr::from_generator(&el);
+        let weights = graph.edges().map(|e| (e.u() * e.v()) as i64).collect::<Vec<i64>>();
+
+        let metis = Metis::new(graph.clone()).with_vertex_weights(vec![1, 2, 3, 4, 5], 1).with_edge_weights(weights.clone());
+        let mut buf = vec![];
+        metis.write(&mut buf).unwrap();
+        let read = Metis::read(&buf[..]).unwrap();
+
+        assert_eq!(read.graph().num_nodes(), 5);
+        assert_eq!(read.edges().collect::<Vec<Edge>>(), graph.edges().collect::<Vec<Edge>>());
+        assert_eq!(read.vertex_weights().unwrap(), &[1, 2, 3, 4, 5]);
+        assert_eq!(read.edge_weights().unwrap(), &weights[..]);
+    }
+
+    #[test]
+    fn asymmetric_rejected() {
+        assert!(Metis::read("3 2\n2\n1 3\n\n".as_bytes()).is_err());
+        assert!(Metis::read("2 1 1\n2 3\n1 4\n".as_bytes()).is_err());
+
+        let mut el = EdgeList::new();
+        el.add_edge(0, 1);
+        el.add_edge(1, 2);
+        el.add_edge(2, 1);
+        el.add_edge(2, 0);
+
+        assert!(Metis::new(Csr::from_generator(&el)).write(&mut vec![]).is_err());
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert!(Metis::read("".as_bytes()).is_err());
+        assert!(Metis::read("2 1 2\n2\n1\n".as_bytes()).is_err());
+        assert!(Metis::read("2 1\n2\n".as_bytes()).is_err());
+        assert!(Metis::read("2 1\n2\n1\n1\n".as_bytes()).is_err());
+        assert!(Metis::read("2 1\n3\n1\n".as_bytes()).is_err());
+        assert!(Metis::read("1 1\n1\n".as_bytes()).is_err());
+        assert!(Metis::read("2 1 10\n\n1\n".as_bytes()).is_err());
+        assert!(Metis::read("18446744073709551615 1\n".as_bytes()).is_err());
+        assert!(Metis::read("4611686018427387904 1\n".as_bytes()).is_err());
+        assert!(Metis::read("2 9223372036854775808\n2\n1\n".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,46 @@
+mod matrix_market;
+mod dimacs;
+mod dot;
+mod graph6;
+mod metis;
+mod labeled;
+mod graphml;
+mod node_link;
+mod binary;
+
+pub use self::matrix_market::MatrixMarket;
+
+pub use self::dimacs::Dimacs;
+pub use self::dimacs::DimacsProblem;
+
+pub use self::dot::to_dot;
+pub use self::dot::highlight_tree;
+pub use self::dot::color_classes;
+pub use self::dot::Dot;
+pub use self::dot::DotGraph;
+
+pub use self::graph6::Graph6;
+pub use self::graph6::Graph6Format;
+pub use self::graph6::Graph6Reader;
+
+pub use self::metis::Metis;
+
+pub use self::labeled::LabeledGraph;
+pub use self::graphml::read_graphml;
+pub use self::graphml::write_graphml;
+pub use self::node_link::read_node_link;
+pub use self::node_link::write_node_link;
+
+pub use self::binary::BinaryFormat;
+pub use self::binary::BinaryHeader;
+pub use self::binary::BinaryLayout;
+pub use self::binary::BinaryOptions;
+
+use std::io;
+
+/// Attribute `(key, value)` pairs of a node or edge.
+pub type Attributes = Vec<(String, String)>;
+
+fn invalid_data<S: Into<String>>(msg: S) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
@@ -0,0 +1,508 @@
+use super::Attributes;
+use super::LabeledGraph;
+use super::invalid_data;
+
+use std::collections::HashSet;
+use std::fmt;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+
+///
+/// Reads JSON node-link data as written by d3 and NetworkX:
+/// `{"directed": .., "nodes": [{"id": ..}, ..], "links": [{"source": .., "target": ..}, ..]}`.
+/// `"edges"` is accepted in place of `"links"` and a missing `"directed"`
+/// means undirected.
+///
+/// Ids may be strings or numbers and are matched by their text. All other
+/// members of nodes and links become attributes: strings hold their content,
+/// other values their compact JSON text.
+///
+pub fn read_node_link<R: BufRead>(mut reader: R) -> io::Result<LabeledGraph> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+
+    let mut parser = JsonParser { input: input.as_bytes(), pos: 0, depth: 0 };
+    let root = parser.parse_document()?;
+
+    let members = match root {
+        Json::Object(members) => members,
+        _ => return Err(invalid_data("node-link data must be a JSON object")),
+    };
+
+    let directed = match members.iter().find(|m| m.0 == "directed").map(|m| &m.1) {
+        Some(&Json::Bool(directed)) => directed,
+        None => false,
+        Some(_) => return Err(invalid_data("'directed' must be a boolean")),
+    };
+
+    let mut graph = LabeledGraph::empty(directed);
+
+    for node in array(&members, &["nodes"])? {
+        let (mut id, attributes) = split(node, &["id"])?;
+        graph.add_node(id.remove(0), attributes)?;
+    }
+
+    for link in array(&members, &["links", "edges"])? {
+        let (ends, attributes) = split(link, &["source", "target"])?;
+        graph.add_edge(&ends[0], &ends[1], attributes)?;
+    }
+
+    Ok(graph)
+}
+
+///
+/// Writes **graph** as JSON node-link data. Node names that are plain
+/// integers are written unquoted, attribute values always as JSON strings.
+///
+pub fn write_node_link<W: Write>(graph: &LabeledGraph, writer: &mut W) -> io::Result<()> {
+    let mut seen = HashSet::new();
+    let multigraph = graph.attributed_edges().any(|(e, _)| !seen.insert(e));
+
+    writeln!(writer, "{{")?;
+    writeln!(writer, "  \"directed\": {},", graph.is_directed())?;
+    writeln!(writer, "  \"multigraph\": {},", multigraph)?;
+    writeln!(writer, "  \"graph\": {{}},")?;
+
+    writeln!(writer, "  \"nodes\": [")?;
+    for v in 0..graph.num_nodes() {
+        let mut members = vec![("id".to_string(), id_value(graph.name(v).unwrap_or_default()))];
+        members.extend(graph.node_attributes(v).into_iter().flatten().map(|a| (a.0.clone(), attribute_value(&a.1))));
+        let separator = if v + 1 < graph.num_nodes() { "," } else { "" };
+        writeln!(writer, "    {}{}", object(&members), separator)?;
+    }
+    writeln!(writer, "  ],")?;
+
+    writeln!(writer, "  \"links\": [")?;
+    let links = graph.attributed_edges().collect::<Vec<_>>();
+    for (i, &(e, attributes)) in links.iter().enumerate() {
+        let mut members = vec![
+            ("source".to_string(), id_value(graph.name(e.u()).unwrap_or_default())),
+            ("target".to_string(), id_value(graph.name(e.v()).unwrap_or_default())),
+        ];
+        members.extend(attributes.iter().map(|a| (a.0.clone(), attribute_value(&a.1))));
+        let separator = if i + 1 < links.len() { "," } else { "" };
+        writeln!(writer, "    {}{}", object(&members), separator)?;
+    }
+    writeln!(writer, "  ]")?;
+
+    writeln!(writer, "}}")
+}
+
+fn array<'a>(members: &'a [(String, Json)], names: &[&str]) -> io::Result<&'a [Json]> {
+    match members.iter().find(|m| names.contains(&&m.0[..])).map(|m| &m.1) {
+        Some(Json::Array(items)) => Ok(items),
+        Some(_) => Err(invalid_data(format!("'{}' must be an array", names[0]))),
+        None => Err(invalid_data(format!("missing '{}'", names[0]))),
+    }
+}
+
+///
+/// Splits an object into the text of the required id members and the
+/// remaining members as attributes.
+///
+fn split(value: &Json, ids: &[&str]) -> io::Result<(Vec<String>, Attributes)> {
+    let members = match *value {
+        Json::Object(ref members) => members,
+        _ => return Err(invalid_data("nodes and links must be JSON objects")),
+    };
+
+    let mut found = vec![];
+    for id in ids {
+        match members.iter().find(|m| m.0 == *id).map(|m| &m.1) {
+            Some(Json::String(s)) => found.push(s.clone()),
+            Some(Json::Number(n)) => found.push(n.clone()),
+            Some(_) => return Err(invalid_data(format!("'{}' must be a string or number", id))),
+            None => return Err(invalid_data(format!("missing '{}'", id))),
+        }
+    }
+
+    let attributes = members.iter()
+        .filter(|m| !ids.contains(&&m.0[..]))
+        .map(|m| match m.1 {
+            Json::String(ref s) => (m.0.clone(), s.clone()),
+            ref other => (m.0.clone(), other.to_string()),
+        })
+        .collect();
+
+    Ok((found, attributes))
+}
+
+fn id_value(name: &str) -> String {
+    let integer = !name.is_empty() && name.bytes().all(|b| b.is_ascii_digit()) && (name == "0" || !name.starts_with('0'));
+    if integer {
+        name.to_string()
+    } else {
+        Json::String(name.to_string()).to_string()
+    }
+}
+
+fn attribute_value(value: &str) -> String {
+    Json::String(value.to_string()).to_string()
+}
+
+fn object(members: &[(String, String)]) -> String {
+    let members = members.iter()
+        .map(|m| format!("{}: {}", Json::String(m.0.clone()), m.1))
+        .collect::<Vec<String>>();
+    format!("{{{}}}", members.join(", "))
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Json {
+    Null,
+    Bool(bool),
+    /// Kept as its source text to avoid rounding.
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(ref n) => write!(f, "{}", n),
+            Json::String(ref s) => {
+                write!(f, "\"")?;
+                for c in s.chars() {
+                    match c {
+                        '"' => write!(f, "\\\"")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        '\r' => write!(f, "\\r")?,
+                        '\t' => write!(f, "\\t")?,
+                        c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+                        c => write!(f, "{}", c)?,
+                    }
+                }
+                write!(f, "\"")
+            }
+            Json::Array(ref items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(ref members) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}:{}", Json::String(key.clone()), value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+///
+/// Arrays and objects nested deeper are rejected rather than risking a
+/// stack overflow on hostile input.
+///
+const MAX_DEPTH: usize = 512;
+
+struct JsonParser<'a> {
+    input: &'a [u8],
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn parse_document(&mut self) -> io::Result<Json> {
+        let value = self.parse_value()?;
+        self.skip_whitespace();
+        if self.pos != self.input.len() {
+            return Err(invalid_data("trailing characters after JSON value"));
+        }
+        Ok(value)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.input.len() && (self.input[self.pos] as char).is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> io::Result<()> {
+        self.skip_whitespace();
+        if self.input.get(self.pos) != Some(&byte) {
+            return Err(invalid_data(format!("expected '{}' at offset {}", byte as char, self.pos)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn literal(&mut self, text: &str, value: Json) -> io::Result<Json> {
+        if self.input[self.pos..].starts_with(text.as_bytes()) {
+            self.pos += text.len();
+            Ok(value)
+        } else {
+            Err(invalid_data(format!("invalid literal at offset {}", self.pos)))
+        }
+    }
+
+    fn parse_value(&mut self) -> io::Result<Json> {
+        self.skip_whitespace();
+
+        match self.input.get(self.pos) {
+            Some(b'[') | Some(b'{') => {
+                if self.depth == MAX_DEPTH {
+                    return Err(invalid_data(format!("JSON nested deeper than {} levels", MAX_DEPTH)));
+                }
+                self.depth += 1;
+                let value = self.parse_container();
+                self.depth -= 1;
+                value
+            }
+            Some(b'n') => self.literal("null", Json::Null),
+            Some(b't') => self.literal("true", Json::Bool(true)),
+            Some(b'f') => self.literal("false", Json::Bool(false)),
+            Some(b'"') => self.parse_string().map(Json::String),
+            Some(&b) if b == b'-' || b.is_ascii_digit() => self.parse_number(),
+            _ => Err(invalid_data(format!("unexpected input at offset {}", self.pos))),
+        }
+    }
+
+    fn parse_container(&mut self) -> io::Result<Json> {
+        match self.input.get(self.pos) {
+            Some(b'[') => {
+                self.pos += 1;
+                let mut items = vec![];
+                self.skip_whitespace();
+                if self.input.get(self.pos) == Some(&b']') {
+                    self.pos += 1;
+                    return Ok(Json::Array(items));
+                }
+                loop {
+                    items.push(self.parse_value()?);
+                    self.skip_whitespace();
+                    match self.input.get(self.pos) {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => {
+                            self.pos += 1;
+                            return Ok(Json::Array(items));
+                        }
+                        _ => return Err(invalid_data(format!("expected ',' or ']' at offset {}", self.pos))),
+                    }
+                }
+            }
+            _ => {
+                self.pos += 1;
+                let mut members = vec![];
+                self.skip_whitespace();
+                if self.input.get(self.pos) == Some(&b'}') {
+                    self.pos += 1;
+                    return Ok(Json::Object(members));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let key = self.parse_string()?;
+                    self.expect(b':')?;
+                    members.push((key, self.parse_value()?));
+                    self.skip_whitespace();
+                    match self.input.get(self.pos) {
+                        Some(b',') => self.pos += 1,
+                        Some(b'}') => {
+                            self.pos += 1;
+                            return Ok(Json::Object(members));
+                        }
+                        _ => return Err(invalid_data(format!("expected ',' or '}}' at offset {}", self.pos))),
+                    }
+                }
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> io::Result<Json> {
+        let start = self.pos;
+        let digits = |p: &mut JsonParser| {
+            let start = p.pos;
+            while p.input.get(p.pos).is_some_and(|b| b.is_ascii_digit()) {
+                p.pos += 1;
+            }
+            p.pos - start
+        };
+
+        if self.input.get(self.pos) == Some(&b'-') {
+            self.pos += 1;
+        }
+        let leading_zero = self.input.get(self.pos) == Some(&b'0');
+        let integer = digits(self);
+        if integer == 0 || (leading_zero && integer > 1) {
+            return Err(invalid_data(format!("invalid number at offset {}", start)));
+        }
+        if self.input.get(self.pos) == Some(&b'.') {
+            self.pos += 1;
+            if digits(self) == 0 {
+                return Err(invalid_data(format!("invalid number at offset {}", start)));
+            }
+        }
+        if let Some(b'e') | Some(b'E') = self.input.get(self.pos) {
+            self.pos += 1;
+            if let Some(b'+') | Some(b'-') = self.input.get(self.pos) {
+                self.pos += 1;
+            }
+            if digits(self) == 0 {
+                return Err(invalid_data(format!("invalid number at offset {}", start)));
+            }
+        }
+
+        let text = String::from_utf8_lossy(&self.input[start..self.pos]).into_owned();
+        Ok(Json::Number(text))
+    }
+
+    fn parse_string(&mut self) -> io::Result<String> {
+        self.expect(b'"')?;
+        let mut bytes = vec![];
+
+        loop {
+            match self.input.get(self.pos) {
+                Some(b'"') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(b'\\') => {
+                    let escaped = self.input.get(self.pos + 1).cloned();
+                    self.pos += 2;
+                    let c = match escaped {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            let mut code = self.hex4()?;
+                            if (0xd800..0xdc00).contains(&code) && self.input[self.pos..].starts_with(b"\\u") {
+                                self.pos += 2;
+                                let low = self.hex4()?;
+                                code = 0x10000 + ((code - 0xd800) << 10) + (low.wrapping_sub(0xdc00) & 0x3ff);
+                            }
+                            ::std::char::from_u32(code).ok_or_else(|| invalid_data("invalid unicode escape"))?
+                        }
+                        _ => return Err(invalid_data(format!("invalid escape at offset {}", self.pos - 2))),
+                    };
+                    let mut buf = [0; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                }
+                Some(&b) => {
+                    bytes.push(b);
+                    self.pos += 1;
+                }
+                None => return Err(invalid_data("unterminated string")),
+            }
+        }
+
+        String::from_utf8(bytes).map_err(|_| invalid_data("invalid UTF-8 in string"))
+    }
+
+    fn hex4(&mut self) -> io::Result<u32> {
+        let digits = self.input.get(self.pos..self.pos + 4).ok_or_else(|| invalid_data("truncated unicode escape"))?;
+        self.pos += 4;
+        ::std::str::from_utf8(digits).ok()
+            .and_then(|d| u32::from_str_radix(d, 16).ok())
+            .ok_or_else(|| invalid_data("invalid unicode escape"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use StaticGraph;
+    use Generator;
+    use Graph;
+    use Edge;
+
+    use representations::AdjacencyList;
+    use formats::LabeledGraph;
+    use formats::read_node_link;
+    use formats::write_node_link;
+
+    const INPUT: &str = r#"{
+        "directed": true,
+        "multigraph": false,
+        "graph": {"name": "example"},
+        "nodes": [
+            {"id": "alice", "age": 31, "tags": ["a", "b"]},
+            {"id": 7},
+            {"id": "bob é\n", "label": "B"}
+        ],
+        "links": [
+            {"source": "alice", "target": 7, "weight": 0.5},
+            {"target": "alice", "source": "bob é\n", "kind": "x"}
+        ]
+    }"#;
+
+    #[test]
+    fn read() {
+        let graph = read_node_link(INPUT.as_bytes()).unwrap();
+
+        assert!(graph.is_directed());
+        assert_eq!(graph.num_nodes(), 3);
+        assert_eq!(graph.node("7"), Some(1));
+        assert_eq!(graph.node("bob \u{e9}\n"), Some(2));
+        assert_eq!(graph.node_attributes(0).unwrap(), &vec![("age".to_string(), "31".to_string()), ("tags".to_string(), "[\"a\",\"b\"]".to_string())]);
+        assert_eq!(graph.edges().collect::<Vec<Edge>>(), vec![Edge::new(0, 1), Edge::new(2, 0)]);
+
+        let edges = graph.attributed_edges().collect::<Vec<_>>();
+        assert_eq!(edges[0].1, &vec![("weight".to_string(), "0.5".to_string())]);
+        assert_eq!(edges[1].1, &vec![("kind".to_string(), "x".to_string())]);
+    }
+
+    #[test]
+    fn roundtrip() {
+        let graph = read_node_link(INPUT.as_bytes()).unwrap();
+
+        let mut buf = vec![];
+        write_node_link(&graph, &mut buf).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        let read = read_node_link(text.as_bytes()).unwrap();
+
+        assert!(text.contains("{\"id\": \"alice\", \"age\": \"31\", \"tags\": \"[\\\"a\\\",\\\"b\\\"]\"}"));
+        assert!(text.contains("{\"id\": 7}"));
+        assert!(text.contains("\"weight\": \"0.5\""));
+        assert_eq!(read.node_attributes(0), graph.node_attributes(0));
+        assert_eq!(read.num_nodes(), 3);
+        assert_eq!(read.edges().collect::<Vec<Edge>>(), graph.edges().collect::<Vec<Edge>>());
+        assert_eq!(read.node_attributes(2), graph.node_attributes(2));
+    }
+
+    #[test]
+    fn undirected_graph() {
+        let mut al = AdjacencyList::new();
+        al.add_edge(0, 1);
+        al.add_edge(1, 0);
+        al.add_edge(1, 2);
+
+        let mut buf = vec![];
+        write_node_link(&LabeledGraph::new(&al, false), &mut buf).unwrap();
+        let read = read_node_link(&buf[..]).unwrap();
+
+        assert!(!read.is_directed());
+        assert_eq!(read.attributed_edges().count(), 2);
+        assert_eq!(AdjacencyList::from_generator(&read).num_edges(), 4);
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert!(read_node_link("[]".as_bytes()).is_err());
+        assert!(read_node_link("{\"nodes\": []}".as_bytes()).is_err());
+        assert!(read_node_link("{\"nodes\": [{\"id\": 1}, {\"id\": 1}], \"links\": []}".as_bytes()).is_err());
+        assert!(read_node_link("{\"nodes\": [{\"id\": 1}], \"links\": [{\"source\": 1, \"target\": 2}]}".as_bytes()).is_err());
+        assert!(read_node_link("{\"nodes\": [{\"name\": 1}], \"links\": []}".as_bytes()).is_err());
+        assert!(read_node_link("{\"nodes\": [], \"links\": [] trailing".as_bytes()).is_err());
+        assert!(read_node_link("{\"nodes\": [01], \"links\": []}".as_bytes()).is_err());
+        assert!(read_node_link(format!("{}{}", "[".repeat(512), "]".repeat(512)).as_bytes()).is_err());
+        assert!(read_node_link("[".repeat(200_000).as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,346 @@
+extern crate rand;
+
+use Generator;
+use Node;
+use Edge;
+
+use self::rand::Rng;
+
+use super::rng;
+use super::rng::GeneratorRng;
+
+///
+/// Constructs a [Barabási–Albert preferential attachment
+/// graph](https://en.wikipedia.org/wiki/Barab%C3%A1si%E2%80%93Albert_model)
+/// with **n** nodes. Starting from an initial graph, every new node attaches
+/// to **m** distinct existing nodes, chosen with probability proportional to
+/// their degree. New edges are generated in both directions.
+///
+/// The initial graph defaults to **m** isolated nodes, which the first new
+/// node connects to. Degrees count the out-edges of the generated graph, so an
+/// initial graph should contain its edges in both directions.
+///
+/// Linear attachment samples from the list of edge endpoints in O(n m).
+/// An initial attractiveness **a** makes the weight `degree + a`, and an
+/// exponent **alpha** makes it `(degree + a)^alpha`, which is sampled in
+/// O(n m log n).
+///
+pub struct BarabasiAlbert {
+    n: usize,
+    m: usize,
+    initial: Vec<Edge>,
+    initial_nodes: usize,
+    a: f64,
+    alpha: f64,
+    seed: u64,
+}
+
+impl BarabasiAlbert {
+    pub fn new(n: usize, m: usize) -> BarabasiAlbert {
+        assert!(m > 0, "new nodes need at least one edge");
+        assert!(m <= n, "the initial graph needs m <= n nodes");
+
+        BarabasiAlbert { n, m, initial: vec![], initial_nodes: m, a: 0.0, alpha: 1.0, seed: rng::random_seed() }
+    }
+
+    ///
+    /// Starts from the edges of **graph**, which needs at least **m** and at
+    /// most **n** nodes.
+    ///
+    pub fn with_initial_graph<G: Generator>(mut self, graph: &G) -> BarabasiAlbert {
+        self.initial = graph.edges().collect();
+
+        let max = self.initial.iter().map(|e| e.u().max(e.v()) + 1).max().unwrap_or(0);
+        self.initial_nodes = graph.num_nodes_hint().unwrap_or(0).max(max);
+
+        assert!(self.initial_nodes >= self.m, "the initial graph needs at least m nodes");
+        assert!(self.initial_nodes <= self.n, "the initial graph has more than n nodes");
+        self
+    }
+
+    pub fn with_attractiveness(mut self, a: f64) -> BarabasiAlbert {
+        assert!(a >= 0.0, "attractiveness must not be negative");
+        self.a = a;
+        self
+    }
+
+    pub fn with_exponent(mut self, alpha: f64) -> BarabasiAlbert {
+        assert!(alpha >= 0.0, "exponent must not be negative");
+        self.alpha = alpha;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> BarabasiAlbert {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Generator for BarabasiAlbert {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let mut rng = rng::seeded(self.seed);
+        let mut attachment = Attachment::new(self.a, self.alpha, self.n);
+
+        for _ in 0..self.initial_nodes {
+            attachment.add_node();
+        }
+        for e in &self.initial {
+            attachment.add_end(e.u());
+        }
+
+        let m = self.m;
+        let grown = (self.initial_nodes..self.n).flat_map(move |v| {
+            let targets = attachment.choose(&mut rng, m);
+
+            attachment.add_node();
+            for &t in &targets {
+                attachment.add_end(v);
+                attachment.add_end(t);
+            }
+
+            targets.into_iter().flat_map(move |t| vec![Edge::new(v, t), Edge::new(t, v)])
+        });
+
+        Box::new(self.initial.iter().cloned().chain(grown))
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+///
+/// Sampling state for preferential attachment. Linear weights sample an
+/// endpoint from `ends`, anything else samples from a Fenwick tree over the
+/// node weights.
+///
+struct Attachment {
+    a: f64,
+    alpha: f64,
+    degrees: Vec<usize>,
+    ends: Vec<Node>,
+    tree: Option<Fenwick>,
+    support: usize,
+    seen: Vec<bool>,
+}
+
+impl Attachment {
+    fn new(a: f64, alpha: f64, n: usize) -> Attachment {
+        let tree = if alpha != 1.0 { Some(Fenwick::new(n)) } else { None };
+        Attachment { a, alpha, degrees: vec![], ends: vec![], tree, support: 0, seen: vec![] }
+    }
+
+    fn weight(&self, degree: usize) -> f64 {
+        (degree as f64 + self.a).powf(self.alpha)
+    }
+
+    fn add_node(&mut self) {
+        let v = self.degrees.len();
+        let w = self.weight(0);
+
+        self.degrees.push(0);
+        self.seen.push(false);
+        if w > 0.0 {
+            self.support += 1;
+        }
+        if let Some(ref mut tree) = self.tree {
+            tree.add(v, w);
+        }
+    }
+
+    fn add_end(&mut self, v: Node) {
+        let before = self.weight(self.degrees[v]);
+        self.degrees[v] += 1;
+        let after = self.weight(self.degrees[v]);
+
+        self.ends.push(v);
+        if before <= 0.0 && after > 0.0 {
+            self.support += 1;
+        }
+        if let Some(ref mut tree) = self.tree {
+            tree.add(v, after - before);
+        }
+    }
+
+    fn sample(&self, rng: &mut GeneratorRng) -> Node {
+        match self.tree {
+            Some(ref tree) => tree.find(rng.gen::<f64>() * tree.total()).min(self.degrees.len() - 1),
+            None => {
+                let uniform = self.a * self.degrees.len() as f64;
+                if self.ends.is_empty() || rng.gen::<f64>() * (self.ends.len() as f64 + uniform) < uniform {
+                    rng.gen_range(0, self.degrees.len())
+                } else {
+                    self.ends[rng.gen_range(0, self.ends.len())]
+                }
+            }
+        }
+    }
+
+    ///
+    /// Chooses **m** distinct nodes. While at most **m** nodes have positive
+    /// weight, all of them are taken and the rest is filled uniformly.
+    ///
+    fn choose(&mut self, rng: &mut GeneratorRng, m: usize) -> Vec<Node> {
+        let mut targets = Vec::with_capacity(m);
+
+        if self.support <= m {
+            for v in 0..self.degrees.len() {
+                if self.weight(self.degrees[v]) > 0.0 {
+                    self.seen[v] = true;
+                    targets.push(v);
+                }
+            }
+        }
+
+        while targets.len() < m {
+            let v = if self.support <= m { rng.gen_range(0, self.degrees.len()) } else { self.sample(rng) };
+            if !self.seen[v] {
+                self.seen[v] = true;
+                targets.push(v);
+            }
+        }
+
+        for &v in &targets {
+            self.seen[v] = false;
+        }
+        targets
+    }
+}
+
+///
+/// Fenwick tree over non-negative weights, for sampling an index with
+/// probability proportional to its weight.
+///
+struct Fenwick {
+    tree: Vec<f64>,
+}
+
+impl Fenwick {
+    fn new(n: usize) -> Fenwick {
+        Fenwick { tree: vec![0.0; n + 1] }
+    }
+
+    fn add(&mut self, i: usize, delta: f64) {
+        let mut i = i + 1;
+        while i < self.tree.len() {
+            self.tree[i] += delta;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    fn total(&self) -> f64 {
+        let mut sum = 0.0;
+        let mut i = self.tree.len() - 1;
+        while i > 0 {
+            sum += self.tree[i];
+            i -= i & i.wrapping_neg();
+        }
+        sum
+    }
+
+    ///
+    /// Smallest index whose prefix sum exceeds **target**.
+    ///
+    fn find(&self, mut target: f64) -> usize {
+        let mut pos = 0;
+        let mut step = (self.tree.len() - 1).next_power_of_two();
+
+        while step > 0 {
+            if pos + step < self.tree.len() && self.tree[pos + step] <= target {
+                pos += step;
+                target -= self.tree[pos];
+            }
+            step /= 2;
+        }
+        pos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use Generator;
+    use StaticGraph;
+    use Edge;
+
+    use representations::AdjacencyList;
+    use generators::BarabasiAlbert;
+    use generators::CompleteGraph;
+    use conformance::is_simple;
+    use conformance::is_symmetric;
+
+    fn max_degree(graph: &AdjacencyList) -> usize {
+        (0..graph.num_nodes()).map(|v| graph.neighbors(v).count()).max().unwrap()
+    }
+
+    #[test]
+    fn edge_count() {
+        let ba = BarabasiAlbert::new(1000, 3).with_seed(1);
+        let edges = ba.edges().collect::<Vec<Edge>>();
+
+        assert_eq!(edges.len(), 2 * 3 * 997);
+        assert!(edges.iter().all(|e| e.u() != e.v() && e.u() < 1000 && e.v() < 1000));
+    }
+
+    #[test]
+    fn simple_and_symmetric() {
+        let graph = AdjacencyList::from_generator(&BarabasiAlbert::new(500, 4).with_seed(2));
+
+        assert!(is_simple(&graph));
+        assert!(is_symmetric(&graph));
+        assert!((0..graph.num_nodes()).all(|v| graph.neighbors(v).count() >= 4));
+    }
+
+    #[test]
+    fn reproducible() {
+        let a = BarabasiAlbert::new(300, 2).with_seed(3);
+        let b = BarabasiAlbert::new(300, 2).with_seed(3);
+
+        assert_eq!(a.edges().collect::<Vec<Edge>>(), a.edges().collect::<Vec<Edge>>());
+        assert_eq!(a.edges().collect::<Vec<Edge>>(), b.edges().collect::<Vec<Edge>>());
+    }
+
+    #[test]
+    fn heavy_tail() {
+        let graph = AdjacencyList::from_generator(&BarabasiAlbert::new(10_000, 2).with_seed(4));
+
+        // Expected maximum degree grows like sqrt(n)
+        assert!(max_degree(&graph) > 50);
+    }
+
+    #[test]
+    fn attractiveness() {
+        let graph = AdjacencyList::from_generator(&BarabasiAlbert::new(10_000, 2).with_attractiveness(1000.0).with_seed(4));
+
+        assert_eq!(graph.num_edges(), 2 * 2 * 9998);
+        assert!(max_degree(&graph) < 30);
+    }
+
+    #[test]
+    fn exponent() {
+        let sublinear = AdjacencyList::from_generator(&BarabasiAlbert::new(5_000, 2).with_exponent(0.5).with_seed(5));
+        let superlinear = AdjacencyList::from_generator(&BarabasiAlbert::new(5_000, 2).with_exponent(1.5).with_seed(5));
+
+        assert_eq!(sublinear.num_edges(), 2 * 2 * 4998);
+        assert_eq!(superlinear.num_edges(), 2 * 2 * 4998);
+        assert!(max_degree(&superlinear) > 2 * max_degree(&sublinear));
+    }
+
+    #[test]
+    fn initial_graph() {
+        let ba = BarabasiAlbert::new(100, 3).with_initial_graph(&CompleteGraph::new(5)).with_seed(6);
+        let edges = ba.edges().collect::<Vec<Edge>>();
+
+        assert_eq!(edges.len(), 25 + 2 * 3 * 95);
+        assert_eq!(&edges[..25], &CompleteGraph::new(5).edges().collect::<Vec<Edge>>()[..]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn small_initial_graph() {
+        BarabasiAlbert::new(100, 3).with_initial_graph(&CompleteGraph::new(2));
+    }
+}
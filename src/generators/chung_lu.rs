@@ -0,0 +1,139 @@
+extern crate rand;
+
+use Generator;
+use Node;
+use Edge;
+
+use self::rand::Rng;
+
+use super::rng;
+use super::util::expand;
+
+///
+/// Constructs a [Chung–Lu](https://doi.org/10.1007/PL00012580) random
+/// graph with given expected degrees. Nodes `u != v` are connected with
+/// probability `min(1, w_u w_v / S)`, where **w** are the weights and `S`
+/// their sum, so node `u` has expected degree close to `w_u` when no
+/// probability is capped.
+///
+/// Pairs are sampled with the skipping algorithm of Miller and Hagberg in
+/// O(n log n + m) rather than testing all n² pairs. Each edge is generated
+/// in both directions.
+///
+pub struct ChungLu {
+    weights: Vec<f64>,
+    seed: u64,
+}
+
+impl ChungLu {
+    pub fn new(weights: Vec<f64>) -> ChungLu {
+        assert!(weights.iter().all(|&w| w >= 0.0), "expected degrees must not be negative");
+        ChungLu { weights, seed: rng::random_seed() }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> ChungLu {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Generator for ChungLu {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let mut rng = rng::seeded(self.seed);
+        let weights = &self.weights;
+        let total = weights.iter().sum::<f64>();
+
+        let mut order = (0..weights.len()).collect::<Vec<Node>>();
+        order.sort_by(|&a, &b| weights[b].partial_cmp(&weights[a]).unwrap().then(a.cmp(&b)));
+
+        let n = order.len();
+        let probability = move |a: Node, b: Node| if total > 0.0 { (weights[a] * weights[b] / total).min(1.0) } else { 0.0 };
+
+        let pairs = (0..n).flat_map(move |i| {
+            let u = order[i];
+            let mut pairs = vec![];
+            let mut j = i + 1;
+            let mut p = if j < n { probability(u, order[j]) } else { 0.0 };
+
+            while j < n && p > 0.0 {
+                j += rng::skip(&mut rng, p).min(n as f64) as usize;
+                if j >= n {
+                    break;
+                }
+
+                // Weights decrease along the order, so p bounds q
+                let q = probability(u, order[j]);
+                if rng.gen::<f64>() < q / p {
+                    pairs.push((u, order[j]));
+                }
+                p = q;
+                j += 1;
+            }
+
+            pairs
+        });
+
+        expand(pairs, true)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.weights.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use Generator;
+    use StaticGraph;
+    use Edge;
+
+    use representations::AdjacencyList;
+    use generators::ChungLu;
+    use conformance::is_simple;
+    use conformance::is_symmetric;
+
+    #[test]
+    fn chung_lu_degrees() {
+        let weights = (0..2000).map(|v| if v < 100 { 50.0 } else { 5.0 }).collect::<Vec<f64>>();
+        let graph = AdjacencyList::from_generator(&ChungLu::new(weights).with_seed(2));
+
+        let heavy = (0..100).map(|v| graph.neighbors(v).count()).sum::<usize>() as f64 / 100.0;
+        let light = (100..2000).map(|v| graph.neighbors(v).count()).sum::<usize>() as f64 / 1900.0;
+
+        assert!(heavy > 45.0 && heavy < 55.0);
+        assert!(light > 4.5 && light < 5.5);
+        assert!(is_simple(&graph));
+        assert!(is_symmetric(&graph));
+    }
+
+    #[test]
+    fn chung_lu_dense() {
+        let graph = AdjacencyList::from_generator(&ChungLu::new(vec![100.0; 10]).with_seed(3));
+
+        assert_eq!(graph.num_edges(), 90);
+    }
+
+    #[test]
+    fn chung_lu_zero_weights() {
+        let graph = AdjacencyList::from_generator(&ChungLu::new(vec![0.0, 3.0, 0.0, 3.0, 3.0]).with_seed(4));
+
+        assert_eq!(graph.num_nodes(), 5);
+        assert_eq!(graph.neighbors(0).count(), 0);
+        assert_eq!(graph.neighbors(2).count(), 0);
+        assert_eq!(ChungLu::new(vec![0.0; 4]).edges().count(), 0);
+    }
+
+    #[test]
+    fn chung_lu_reproducible() {
+        let weights = (0..500).map(|v| 1.0 + (v % 7) as f64).collect::<Vec<f64>>();
+        let a = ChungLu::new(weights.clone()).with_seed(5);
+        let b = ChungLu::new(weights).with_seed(5);
+
+        assert_eq!(a.edges().collect::<Vec<Edge>>(), a.edges().collect::<Vec<Edge>>());
+        assert_eq!(a.edges().collect::<Vec<Edge>>(), b.edges().collect::<Vec<Edge>>());
+    }
+}
@@ -0,0 +1,395 @@
+extern crate rand;
+
+use Generator;
+use Node;
+use Edge;
+
+use self::rand::Rng;
+
+use super::rng;
+
+use std::collections::HashSet;
+use std::iter;
+use std::sync::Arc;
+
+///
+/// Adapters on any `Generator` that are generators themselves, so inputs can
+/// be composed before building a representation:
+///
+/// ```ignore
+/// let gen = Erdos::new(100, 0.1).with_seed(1).without_self_loops().shuffle_nodes(7);
+/// let graph = Csr::from_generator(&gen);
+/// ```
+///
+pub trait GeneratorExt : Generator + Sized {
+    ///
+    /// Maps every endpoint through **f**. **f** is shared between the
+    /// threads generating edge chunks, so it must be `Sync`.
+    ///
+    fn relabel<F: Fn(Node) -> Node + Sync>(self, f: F) -> Relabel<Self, F> {
+        Relabel { inner: self, f }
+    }
+
+    ///
+    /// Relabels the nodes by a random permutation drawn from **seed**.
+    ///
+    fn shuffle_nodes(self, seed: u64) -> ShuffleNodes<Self> {
+        ShuffleNodes { inner: self, seed }
+    }
+
+    ///
+    /// Keeps the edges for which **predicate** holds. Like the function of
+    /// `relabel`, **predicate** must be `Sync`.
+    ///
+    fn filter_edges<P: Fn(&Edge) -> bool + Sync>(self, predicate: P) -> FilterEdges<Self, P> {
+        FilterEdges { inner: self, predicate }
+    }
+
+    ///
+    /// Adds the reverse of every edge. Each distinct edge is generated once,
+    /// so this also removes multi-edges.
+    ///
+    fn symmetrize(self) -> Symmetrize<Self> {
+        Symmetrize { inner: self }
+    }
+
+    ///
+    /// Generates the edges of **other** after those of this generator.
+    ///
+    fn chain<G: Generator>(self, other: G) -> Chain<Self, G> {
+        Chain { first: self, second: other }
+    }
+
+    ///
+    /// Keeps the first **m** edges.
+    ///
+    fn take(self, m: usize) -> Take<Self> {
+        Take { inner: self, m }
+    }
+
+    ///
+    /// Adds **k** to every endpoint, e.g. to place a generator after another
+    /// in a `chain`.
+    ///
+    fn offset_nodes(self, k: usize) -> OffsetNodes<Self> {
+        OffsetNodes { inner: self, k }
+    }
+
+    ///
+    /// Drops repeated edges, keeping the first occurrence.
+    ///
+    fn dedup(self) -> Dedup<Self> {
+        Dedup { inner: self }
+    }
+
+    fn without_self_loops(self) -> WithoutSelfLoops<Self> {
+        WithoutSelfLoops { inner: self }
+    }
+}
+
+impl<G: Generator> GeneratorExt for G {}
+
+pub struct Relabel<G, F> {
+    inner: G,
+    f: F,
+}
+
+impl<G: Generator, F: Fn(Node) -> Node + Sync> Generator for Relabel<G, F> {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let f = &self.f;
+        Box::new(self.inner.edges().map(move |e| Edge::new(f(e.u()), f(e.v()))))
+    }
+
+    fn edge_chunks<'a>(&'a self) -> Option<Vec<Box<dyn Iterator<Item=Edge> + Send + 'a>>> {
+        let f = &self.f;
+        self.inner.edge_chunks().map(|chunks| chunks.into_iter().map(|chunk| {
+            Box::new(chunk.map(move |e| Edge::new(f(e.u()), f(e.v())))) as Box<dyn Iterator<Item=Edge> + Send>
+        }).collect())
+    }
+}
+
+pub struct ShuffleNodes<G> {
+    inner: G,
+    seed: u64,
+}
+
+impl<G> ShuffleNodes<G> where G: Generator {
+    fn permutation(&self) -> Vec<Node> {
+        let n = self.inner.num_nodes_hint().unwrap_or_else(|| self.inner.edges().map(|e| e.u().max(e.v()) + 1).max().unwrap_or(0));
+        let mut permutation = (0..n).collect::<Vec<Node>>();
+        rng::seeded(self.seed).shuffle(&mut permutation);
+        permutation
+    }
+}
+
+impl<G: Generator> Generator for ShuffleNodes<G> {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let permutation = self.permutation();
+        Box::new(self.inner.edges().map(move |e| Edge::new(permutation[e.u()], permutation[e.v()])))
+    }
+
+    fn edge_chunks<'a>(&'a self) -> Option<Vec<Box<dyn Iterator<Item=Edge> + Send + 'a>>> {
+        let chunks = self.inner.edge_chunks()?;
+        let permutation = Arc::new(self.permutation());
+        Some(chunks.into_iter().map(|chunk| {
+            let permutation = permutation.clone();
+            Box::new(chunk.map(move |e| Edge::new(permutation[e.u()], permutation[e.v()]))) as Box<dyn Iterator<Item=Edge> + Send>
+        }).collect())
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        self.inner.num_nodes_hint()
+    }
+}
+
+pub struct FilterEdges<G, P> {
+    inner: G,
+    predicate: P,
+}
+
+impl<G: Generator, P: Fn(&Edge) -> bool + Sync> Generator for FilterEdges<G, P> {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        Box::new(self.inner.edges().filter(move |e| (self.predicate)(e)))
+    }
+
+    fn edge_chunks<'a>(&'a self) -> Option<Vec<Box<dyn Iterator<Item=Edge> + Send + 'a>>> {
+        let predicate = &self.predicate;
+        self.inner.edge_chunks().map(|chunks| chunks.into_iter().map(|chunk| {
+            Box::new(chunk.filter(move |e| predicate(e))) as Box<dyn Iterator<Item=Edge> + Send>
+        }).collect())
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        self.inner.num_nodes_hint()
+    }
+}
+
+pub struct Symmetrize<G> {
+    inner: G,
+}
+
+impl<G: Generator> Generator for Symmetrize<G> {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let mut seen = HashSet::new();
+        Box::new(self.inner.edges().flat_map(|e| iter::once(e).chain(iter::once(Edge::new(e.v(), e.u())))).filter(move |&e| seen.insert(e)))
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        self.inner.num_nodes_hint()
+    }
+}
+
+pub struct Chain<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: Generator, B: Generator> Generator for Chain<A, B> {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        Box::new(self.first.edges().chain(self.second.edges()))
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        match (self.first.num_nodes_hint(), self.second.num_nodes_hint()) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            _ => None,
+        }
+    }
+}
+
+pub struct Take<G> {
+    inner: G,
+    m: usize,
+}
+
+impl<G: Generator> Generator for Take<G> {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        Box::new(self.inner.edges().take(self.m))
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        self.inner.num_nodes_hint()
+    }
+}
+
+pub struct OffsetNodes<G> {
+    inner: G,
+    k: usize,
+}
+
+impl<G: Generator> Generator for OffsetNodes<G> {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let k = self.k;
+        Box::new(self.inner.edges().map(move |e| Edge::new(e.u() + k, e.v() + k)))
+    }
+
+    fn edge_chunks<'a>(&'a self) -> Option<Vec<Box<dyn Iterator<Item=Edge> + Send + 'a>>> {
+        let k = self.k;
+        self.inner.edge_chunks().map(|chunks| chunks.into_iter().map(|chunk| {
+            Box::new(chunk.map(move |e| Edge::new(e.u() + k, e.v() + k))) as Box<dyn Iterator<Item=Edge> + Send>
+        }).collect())
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        self.inner.num_nodes_hint().map(|n| n + self.k)
+    }
+}
+
+pub struct Dedup<G> {
+    inner: G,
+}
+
+impl<G: Generator> Generator for Dedup<G> {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let mut seen = HashSet::new();
+        Box::new(self.inner.edges().filter(move |&e| seen.insert(e)))
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        self.inner.num_nodes_hint()
+    }
+}
+
+pub struct WithoutSelfLoops<G> {
+    inner: G,
+}
+
+impl<G: Generator> Generator for WithoutSelfLoops<G> {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        Box::new(self.inner.edges().filter(|e| e.u() != e.v()))
+    }
+
+    fn edge_chunks<'a>(&'a self) -> Option<Vec<Box<dyn Iterator<Item=Edge> + Send + 'a>>> {
+        self.inner.edge_chunks().map(|chunks| chunks.into_iter().map(|chunk| {
+            Box::new(chunk.filter(|e| e.u() != e.v())) as Box<dyn Iterator<Item=Edge> + Send>
+        }).collect())
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        self.inner.num_nodes_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use Generator;
+    use StaticGraph;
+    use Graph;
+    use Edge;
+
+    use representations::Csr;
+    use representations::EdgeList;
+    use generators::Erdos;
+    use generators::Rmat;
+    use generators::Path;
+    use generators::GeneratorExt;
+
+    fn edges<G: Generator>(gen: &G) -> Vec<(usize, usize)> {
+        gen.edges().map(|e| (e.u(), e.v())).collect()
+    }
+
+    #[test]
+    fn relabel() {
+        let gen = Path::new(3).relabel(|v| 10 * v);
+
+        assert_eq!(edges(&gen), vec![(0, 10), (10, 20)]);
+        assert_eq!(gen.num_nodes_hint(), None);
+    }
+
+    #[test]
+    fn shuffle_nodes() {
+        let erdos = Erdos::new(50, 0.2).with_seed(1);
+        let gen = Erdos::new(50, 0.2).with_seed(1).shuffle_nodes(2);
+
+        let mut degrees = erdos.edges().fold(vec![0; 50], |mut d, e| { d[e.u()] += 1; d });
+        let mut shuffled = gen.edges().fold(vec![0; 50], |mut d, e| { d[e.u()] += 1; d });
+        degrees.sort();
+        shuffled.sort();
+
+        assert_eq!(degrees, shuffled);
+        assert!(edges(&gen) != edges(&erdos));
+        assert_eq!(edges(&gen), edges(&Erdos::new(50, 0.2).with_seed(1).shuffle_nodes(2)));
+        assert_eq!(gen.num_nodes_hint(), Some(50));
+    }
+
+    #[test]
+    fn shuffle_without_hint() {
+        let gen = Path::new(4).relabel(|v| v).shuffle_nodes(3);
+        let graph = EdgeList::from_generator(&gen);
+
+        assert_eq!(graph.num_nodes(), 4);
+        assert_eq!(graph.num_edges(), 3);
+    }
+
+    #[test]
+    fn filter_edges() {
+        let gen = Path::new(5).undirected().filter_edges(|e| e.u() < e.v());
+
+        assert_eq!(edges(&gen), vec![(0, 1), (1, 2), (2, 3), (3, 4)]);
+        assert_eq!(gen.num_nodes_hint(), Some(5));
+    }
+
+    #[test]
+    fn symmetrize() {
+        let gen = EdgeList::from_generator(&Path::new(3).chain(Path::new(2))).symmetrize();
+
+        assert_eq!(edges(&gen), vec![(0, 1), (1, 0), (1, 2), (2, 1)]);
+    }
+
+    #[test]
+    fn chain_and_offset() {
+        let gen = Path::new(3).chain(Path::new(2).offset_nodes(3));
+        let graph = Csr::from_generator(&gen);
+
+        assert_eq!(edges(&gen), vec![(0, 1), (1, 2), (3, 4)]);
+        assert_eq!(gen.num_nodes_hint(), Some(5));
+        assert_eq!(graph.num_nodes(), 5);
+        assert_eq!(Path::new(3).chain(Path::new(2).relabel(|v| v)).num_nodes_hint(), None);
+    }
+
+    #[test]
+    fn take() {
+        let gen = Path::new(5).take(2);
+
+        assert_eq!(edges(&gen), vec![(0, 1), (1, 2)]);
+        assert_eq!(gen.num_nodes_hint(), Some(5));
+        assert!(gen.edge_chunks().is_none());
+        assert_eq!(edges(&Path::new(3).take(10)), vec![(0, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn dedup_and_loops() {
+        let mut graph = EdgeList::new();
+        for &(u, v) in &[(0, 0), (0, 1), (0, 1), (1, 1), (1, 0)] {
+            graph.add_edge(u, v);
+        }
+
+        assert_eq!(edges(&(&graph).dedup()), vec![(0, 0), (0, 1), (1, 1), (1, 0)]);
+        assert_eq!(edges(&(&graph).without_self_loops()), vec![(0, 1), (0, 1), (1, 0)]);
+        assert_eq!(edges(&(&graph).dedup().without_self_loops()), vec![(0, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn composed() {
+        let gen = Erdos::new(100, 0.1).with_seed(4).without_self_loops().shuffle_nodes(5);
+        let graph = Csr::from_generator(&gen);
+
+        assert_eq!(graph.num_nodes(), 100);
+        assert_eq!(graph.num_edges(), gen.edges().count());
+        assert!((0..100).all(|v| !graph.has_edge(v, v)));
+    }
+
+    #[test]
+    fn chunks() {
+        let gen = Rmat::new(10, 100).with_seed(6).without_self_loops().filter_edges(|e| e.u() < e.v()).relabel(|v| 2 * v).shuffle_nodes(7).offset_nodes(1);
+        let edges = gen.edges().collect::<Vec<Edge>>();
+
+        assert_eq!(gen.edge_chunks().map(|chunks| chunks.len()), Some(2));
+        assert_eq!(gen.edges_parallel(3).concat(), edges);
+
+        let (parallel, sequential) = (Csr::from_generator_parallel(&gen, 2), Csr::from_generator(&gen));
+        assert_eq!(parallel.row(), sequential.row());
+        assert_eq!(parallel.col(), sequential.col());
+        assert!(Path::new(5).relabel(|v| v).edge_chunks().is_none());
+    }
+}
@@ -0,0 +1,186 @@
+extern crate rand;
+
+use Generator;
+use Edge;
+
+use self::rand::Rng;
+
+use super::rng;
+use super::util::expand;
+
+///
+/// Constructs a [copying model](https://doi.org/10.1109/SFCS.2000.892065)
+/// graph with **n** nodes. Every new node picks a uniformly random prototype
+/// among the existing nodes and creates **out_degree** links: the `i`th one
+/// copies the prototype's `i`th link with probability **copy**, and goes to
+/// a uniformly random existing node otherwise, or when the prototype has no
+/// such link. Links of a node are distinct, and the first nodes link to all
+/// older ones.
+///
+/// With densification, node `t` instead gets about
+/// `out_degree * ((t + 1)^exponent - t^exponent)` links, so the graph has
+/// about `out_degree * t^exponent` edges after `t` nodes, following the
+/// densification power law.
+///
+/// Edges point from each new node to older ones and are generated in node
+/// arrival order, so they double as a temporal stream.
+///
+pub struct CopyingModel {
+    n: usize,
+    out_degree: usize,
+    copy: f64,
+    exponent: f64,
+    undirected: bool,
+    seed: u64,
+}
+
+impl CopyingModel {
+    pub fn new(n: usize, out_degree: usize, copy: f64) -> CopyingModel {
+        assert!((0.0..=1.0).contains(&copy), "copy probability must lie in [0, 1]");
+        CopyingModel { n, out_degree, copy, exponent: 1.0, undirected: false, seed: rng::random_seed() }
+    }
+
+    ///
+    /// Grows the number of edges as `t^exponent` in the number of nodes `t`.
+    ///
+    pub fn with_densification(mut self, exponent: f64) -> CopyingModel {
+        assert!((1.0..=2.0).contains(&exponent), "densification exponent must lie in [1, 2]");
+        self.exponent = exponent;
+        self
+    }
+
+    pub fn undirected(mut self) -> CopyingModel {
+        self.undirected = true;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> CopyingModel {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    ///
+    /// Number of links created by node **t**.
+    ///
+    fn links(&self, t: usize) -> usize {
+        let total = |t: usize| (self.out_degree as f64 * (t as f64).powf(self.exponent)).round() as usize;
+        (total(t + 1) - total(t)).min(t)
+    }
+}
+
+impl Generator for CopyingModel {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let mut rng = rng::seeded(self.seed);
+        let n = self.n;
+
+        let mut out_neighbors = vec![vec![]; n];
+        let mut linked_by = vec![usize::MAX; n];
+        let mut pairs = vec![];
+
+        for v in 1..n {
+            let links = self.links(v);
+            let mut targets = Vec::with_capacity(links);
+
+            if links == v {
+                targets.extend(0..v);
+            } else {
+                let prototype = rng.gen_range(0, v);
+                for i in 0..links {
+                    let copied = out_neighbors[prototype].get(i).cloned();
+                    let mut u = match copied {
+                        Some(u) if rng.gen::<f64>() < self.copy => u,
+                        _ => rng.gen_range(0, v),
+                    };
+
+                    while linked_by[u] == v {
+                        u = rng.gen_range(0, v);
+                    }
+                    linked_by[u] = v;
+                    targets.push(u);
+                }
+            }
+
+            for &u in &targets {
+                pairs.push((v, u));
+            }
+            out_neighbors[v] = targets;
+        }
+
+        expand(pairs.into_iter(), self.undirected)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use Generator;
+    use StaticGraph;
+    use Edge;
+
+    use representations::AdjacencyList;
+    use generators::CopyingModel;
+    use conformance::is_simple;
+
+    fn in_degrees(edges: &[Edge], n: usize) -> Vec<usize> {
+        edges.iter().fold(vec![0; n], |mut d, e| {
+            d[e.v()] += 1;
+            d
+        })
+    }
+
+    #[test]
+    fn out_degrees() {
+        let model = CopyingModel::new(1000, 5, 0.7).with_seed(1);
+        let edges = model.edges().collect::<Vec<Edge>>();
+        let graph = AdjacencyList::from_generator(&model);
+
+        assert!(edges.iter().all(|e| e.u() > e.v()));
+        assert!(edges.windows(2).all(|w| w[0].u() <= w[1].u()));
+        assert!((0..1000).all(|v| graph.neighbors(v).count() == v.min(5)));
+        assert!(is_simple(&graph));
+    }
+
+    #[test]
+    fn copying_skews_degrees() {
+        let uniform = CopyingModel::new(5000, 4, 0.0).with_seed(2).edges().collect::<Vec<Edge>>();
+        let copied = CopyingModel::new(5000, 4, 0.9).with_seed(2).edges().collect::<Vec<Edge>>();
+
+        let max = |edges: &[Edge]| in_degrees(edges, 5000).into_iter().max().unwrap();
+        assert!(max(&copied) > 2 * max(&uniform));
+        assert_eq!(uniform.len(), copied.len());
+    }
+
+    #[test]
+    fn densification() {
+        let model = CopyingModel::new(4000, 3, 0.5).with_densification(1.3).with_seed(3);
+        let edges = model.edges().collect::<Vec<Edge>>();
+        let count = |n: usize| edges.iter().filter(|e| e.u() < n).count() as f64;
+
+        // e(n) ~ 3 n^1.3, so the log-log slope is 1.3
+        let slope = (count(4000) / count(500)).ln() / 8f64.ln();
+        assert!((slope - 1.3).abs() < 0.02);
+        assert!((count(4000) / (3.0 * 4000f64.powf(1.3)) - 1.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn undirected() {
+        let model = CopyingModel::new(200, 3, 0.5).with_seed(4);
+        let graph = AdjacencyList::from_generator(&model.undirected());
+
+        assert_eq!(graph.num_edges(), 2 * (3 * 197 + 3));
+        assert!(graph.edges().all(|e| graph.has_edge(e.v(), e.u())));
+    }
+
+    #[test]
+    fn reproducible() {
+        let model = CopyingModel::new(300, 4, 0.5).with_densification(1.2).with_seed(5);
+        assert_eq!(model.edges().collect::<Vec<Edge>>(), model.edges().collect::<Vec<Edge>>());
+    }
+}
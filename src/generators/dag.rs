@@ -0,0 +1,236 @@
+extern crate rand;
+
+use Generator;
+use Node;
+use Edge;
+
+use self::rand::Rng;
+
+use super::rng;
+use super::rng::GeneratorRng;
+use super::erdos::Pairs;
+
+use std::iter;
+
+///
+/// Constructs a random directed acyclic graph on **n** nodes: G(n, p)
+/// restricted to the edges that go forward in a topological order. The order
+/// is the identity unless shuffled, in which case `order()` reports it.
+///
+/// Sampled by geometric skipping in O(n + m).
+///
+pub struct RandomDag {
+    n: usize,
+    p: f64,
+    shuffled: bool,
+    seed: u64,
+}
+
+impl RandomDag {
+    pub fn new(n: usize, p: f64) -> RandomDag {
+        RandomDag { n, p, shuffled: false, seed: rng::random_seed() }
+    }
+
+    ///
+    /// Relabels the nodes by a random permutation, so the topological order
+    /// is no longer the identity.
+    ///
+    pub fn shuffled(mut self) -> RandomDag {
+        self.shuffled = true;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> RandomDag {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    ///
+    /// The topological order all edges follow.
+    ///
+    pub fn order(&self) -> Vec<Node> {
+        self.draw_order(&mut rng::seeded(self.seed))
+    }
+
+    fn draw_order(&self, rng: &mut GeneratorRng) -> Vec<Node> {
+        let mut order = (0..self.n).collect::<Vec<Node>>();
+        if self.shuffled {
+            rng.shuffle(&mut order);
+        }
+        order
+    }
+}
+
+impl Generator for RandomDag {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let mut rng = rng::seeded(self.seed);
+        let order = self.draw_order(&mut rng);
+
+        // Pairs (u, v) with v < u, taken as the edge from position v to u
+        let mut pairs = Pairs::new(self.n, false, true);
+        let total = pairs.total() as f64;
+        let p = self.p;
+
+        Box::new(iter::from_fn(move || {
+            let skip = rng::skip(&mut rng, p);
+            if skip >= total {
+                return None;
+            }
+
+            pairs.advance(skip as u64).map(|(u, v)| Edge::new(order[v], order[u]))
+        }))
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+///
+/// Constructs a layered random DAG with **layers** layers of **width**
+/// nodes. Node `layer * width + i` is the `i`th node of its layer, and each
+/// pair of nodes in consecutive layers is joined by an edge pointing to the
+/// later layer with probability **p**.
+///
+pub struct LayeredDag {
+    layers: usize,
+    width: usize,
+    p: f64,
+    seed: u64,
+}
+
+impl LayeredDag {
+    pub fn new(layers: usize, width: usize, p: f64) -> LayeredDag {
+        LayeredDag { layers, width, p, seed: rng::random_seed() }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> LayeredDag {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn layer(&self, node: Node) -> Option<usize> {
+        if node < self.layers * self.width {
+            Some(node / self.width)
+        } else {
+            None
+        }
+    }
+}
+
+impl Generator for LayeredDag {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let mut rng = rng::seeded(self.seed);
+        let width = self.width;
+        let total = (self.layers.saturating_sub(1) * width * width) as f64;
+        let p = self.p;
+        let mut position = 0.0;
+
+        // Layer pairs are laid out one after another, each row-major
+        Box::new(iter::from_fn(move || {
+            position += rng::skip(&mut rng, p);
+            if position >= total {
+                return None;
+            }
+
+            let c = position as usize;
+            position += 1.0;
+
+            let (layer, offset) = (c / (width * width), c % (width * width));
+            let u = layer * width + offset / width;
+            Some(Edge::new(u, (layer + 1) * width + offset % width))
+        }))
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.layers * self.width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use Generator;
+    use StaticGraph;
+    use Edge;
+
+    use representations::AdjacencyList;
+    use generators::RandomDag;
+    use generators::LayeredDag;
+
+    #[test]
+    fn dag() {
+        let dag = RandomDag::new(300, 0.05).with_seed(1);
+        let edges = dag.edges().collect::<Vec<Edge>>();
+
+        assert!(edges.iter().all(|e| e.u() < e.v()));
+        assert!(edges.len() > 1900 && edges.len() < 2600);
+        assert_eq!(dag.order(), (0..300).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn shuffled_dag() {
+        let dag = RandomDag::new(200, 0.1).shuffled().with_seed(2);
+        let order = dag.order();
+
+        let mut position = vec![0; 200];
+        for (i, &v) in order.iter().enumerate() {
+            position[v] = i;
+        }
+
+        let mut sorted = order.clone();
+        sorted.sort();
+
+        assert_eq!(sorted, (0..200).collect::<Vec<_>>());
+        assert!(order != sorted);
+        assert!(dag.edges().all(|e| position[e.u()] < position[e.v()]));
+        assert!(dag.edges().any(|e| e.u() > e.v()));
+    }
+
+    #[test]
+    fn complete_dag() {
+        let graph = AdjacencyList::from_generator(&RandomDag::new(6, 1.0).with_seed(3));
+
+        assert_eq!(graph.num_edges(), 15);
+        assert!((0..6).all(|v| graph.neighbors(v).count() == 5 - v));
+        assert_eq!(RandomDag::new(6, 0.0).edges().count(), 0);
+    }
+
+    #[test]
+    fn layered() {
+        let dag = LayeredDag::new(5, 20, 0.3).with_seed(4);
+        let graph = AdjacencyList::from_generator(&dag);
+
+        assert_eq!(graph.num_nodes(), 100);
+        assert!(graph.edges().all(|e| dag.layer(e.v()) == dag.layer(e.u()).map(|l| l + 1)));
+        assert!(graph.num_edges() > 400 && graph.num_edges() < 560);
+        assert_eq!(dag.layer(100), None);
+    }
+
+    #[test]
+    fn complete_layers() {
+        let graph = AdjacencyList::from_generator(&LayeredDag::new(3, 2, 1.0).with_seed(5));
+        let pred = graph.breadth_first_search(0);
+
+        assert_eq!(graph.num_edges(), 8);
+        assert!(graph.has_edge(1, 2) && graph.has_edge(3, 4) && !graph.has_edge(0, 4));
+        assert_eq!(pred[4], Some(2));
+        assert_eq!(pred[1], None);
+    }
+
+    #[test]
+    fn reproducible() {
+        let a = RandomDag::new(100, 0.1).shuffled().with_seed(6);
+        let b = LayeredDag::new(10, 10, 0.2).with_seed(6);
+
+        assert_eq!(a.edges().collect::<Vec<Edge>>(), a.edges().collect::<Vec<Edge>>());
+        assert_eq!(b.edges().collect::<Vec<Edge>>(), b.edges().collect::<Vec<Edge>>());
+    }
+}
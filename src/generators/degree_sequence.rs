@@ -0,0 +1,466 @@
+extern crate rand;
+
+use Generator;
+use StaticGraph;
+use Node;
+use Edge;
+use Error;
+use Result;
+
+use self::rand::Rng;
+
+use super::rng;
+use super::util::expand;
+
+use std::collections::HashSet;
+
+///
+/// Constructs a [configuration
+/// model](https://en.wikipedia.org/wiki/Configuration_model) graph by
+/// randomly matching degree stubs. An undirected model pairs up the stubs of
+/// a degree sequence and generates each edge in both directions, with a
+/// self-loop using two stubs of its node. A directed model matches
+/// out-stubs to in-stubs.
+///
+/// The degrees are reproduced exactly, at the cost of loops and multi-edges.
+/// The erased model drops those, which lowers some degrees.
+///
+pub struct ConfigurationModel {
+    out_degrees: Vec<usize>,
+    in_degrees: Option<Vec<usize>>,
+    loops: bool,
+    multi_edges: bool,
+    seed: u64,
+}
+
+impl ConfigurationModel {
+    ///
+    /// Undirected model for **degrees**, which must have an even sum.
+    ///
+    pub fn new(degrees: Vec<usize>) -> Result<ConfigurationModel> {
+        if !degrees.iter().sum::<usize>().is_multiple_of(2) {
+            return Err(Error::NotGraphical);
+        }
+
+        Ok(ConfigurationModel { out_degrees: degrees, in_degrees: None, loops: true, multi_edges: true, seed: rng::random_seed() })
+    }
+
+    ///
+    /// Directed model for **out_degrees** and **in_degrees**, which must
+    /// have the same length and sum.
+    ///
+    pub fn directed(out_degrees: Vec<usize>, in_degrees: Vec<usize>) -> Result<ConfigurationModel> {
+        if out_degrees.len() != in_degrees.len() || out_degrees.iter().sum::<usize>() != in_degrees.iter().sum::<usize>() {
+            return Err(Error::NotGraphical);
+        }
+
+        Ok(ConfigurationModel { out_degrees, in_degrees: Some(in_degrees), loops: true, multi_edges: true, seed: rng::random_seed() })
+    }
+
+    ///
+    /// Directed model preserving the out- and in-degrees of **graph**.
+    ///
+    pub fn from_graph<G: StaticGraph>(graph: &G) -> ConfigurationModel {
+        let n = graph.num_nodes();
+        let out_degrees = (0..n).map(|v| graph.neighbors(v).count()).collect();
+
+        let mut in_degrees = vec![0; n];
+        for e in graph.edges() {
+            in_degrees[e.v()] += 1;
+        }
+
+        ConfigurationModel { out_degrees, in_degrees: Some(in_degrees), loops: true, multi_edges: true, seed: rng::random_seed() }
+    }
+
+    pub fn without_loops(mut self) -> ConfigurationModel {
+        self.loops = false;
+        self
+    }
+
+    pub fn without_multi_edges(mut self) -> ConfigurationModel {
+        self.multi_edges = false;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> ConfigurationModel {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Generator for ConfigurationModel {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let mut rng = rng::seeded(self.seed);
+        let stubs = |degrees: &[usize]| degrees.iter().enumerate().flat_map(|(v, &d)| (0..d).map(move |_| v)).collect::<Vec<Node>>();
+
+        let mut out_stubs = stubs(&self.out_degrees);
+        rng.shuffle(&mut out_stubs);
+
+        let pairs = match self.in_degrees {
+            Some(ref in_degrees) => {
+                let mut in_stubs = stubs(in_degrees);
+                rng.shuffle(&mut in_stubs);
+                out_stubs.into_iter().zip(in_stubs).collect::<Vec<(Node, Node)>>()
+            }
+            None => out_stubs.chunks(2).map(|pair| (pair[0], pair[1])).collect(),
+        };
+
+        let undirected = self.in_degrees.is_none();
+        let loops = self.loops;
+        let mut seen = if self.multi_edges { None } else { Some(HashSet::new()) };
+
+        let pairs = pairs.into_iter().filter(move |&(u, v)| {
+            let key = if undirected { (u.min(v), u.max(v)) } else { (u, v) };
+            (loops || u != v) && seen.as_mut().is_none_or(|seen| seen.insert(key))
+        });
+
+        expand(pairs, undirected)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.out_degrees.len())
+    }
+}
+
+///
+/// Deterministic simple undirected graph with the given degree sequence,
+/// built by the [Havel–Hakimi
+/// algorithm](https://en.wikipedia.org/wiki/Havel%E2%80%93Hakimi_algorithm).
+/// The node of highest remaining degree is repeatedly connected to the nodes
+/// of next highest degree, with ties broken by node id. Each edge is
+/// generated in both directions.
+///
+pub struct HavelHakimi {
+    n: usize,
+    pairs: Vec<(Node, Node)>,
+}
+
+impl HavelHakimi {
+    pub fn new(degrees: &[usize]) -> Result<HavelHakimi> {
+        let mut remaining = degrees.iter().cloned().enumerate().filter(|&(_, d)| d > 0).map(|(v, d)| (d, v)).collect::<Vec<(usize, Node)>>();
+        let mut pairs = vec![];
+
+        loop {
+            remaining.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+            remaining.retain(|&(d, _)| d > 0);
+
+            if remaining.is_empty() {
+                break;
+            }
+
+            let (d, u) = remaining[0];
+            if d >= remaining.len() {
+                return Err(Error::NotGraphical);
+            }
+
+            for entry in &mut remaining[1..d + 1] {
+                entry.0 -= 1;
+                pairs.push((u, entry.1));
+            }
+            remaining[0].0 = 0;
+        }
+
+        Ok(HavelHakimi { n: degrees.len(), pairs })
+    }
+}
+
+impl Generator for HavelHakimi {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        expand(self.pairs.iter().cloned(), true)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+///
+/// Deterministic simple directed graph with the given out- and in-degree
+/// sequences, built by the Kleitman–Wang algorithm. Each node in turn sends
+/// its out-edges to the other nodes of highest remaining in-degree, with
+/// ties broken by remaining out-degree and then node id.
+///
+pub struct KleitmanWang {
+    n: usize,
+    pairs: Vec<(Node, Node)>,
+}
+
+impl KleitmanWang {
+    pub fn new(out_degrees: &[usize], in_degrees: &[usize]) -> Result<KleitmanWang> {
+        let n = out_degrees.len();
+        if in_degrees.len() != n || out_degrees.iter().sum::<usize>() != in_degrees.iter().sum::<usize>() {
+            return Err(Error::NotGraphical);
+        }
+
+        let mut out = out_degrees.to_vec();
+        let mut inn = in_degrees.to_vec();
+        let mut pairs = vec![];
+
+        let mut order = (0..n).collect::<Vec<Node>>();
+        order.sort_by(|&a, &b| out[b].cmp(&out[a]).then(inn[b].cmp(&inn[a])).then(a.cmp(&b)));
+
+        for u in order {
+            let mut targets = (0..n).filter(|&v| v != u && inn[v] > 0).collect::<Vec<Node>>();
+            if targets.len() < out[u] {
+                return Err(Error::NotGraphical);
+            }
+
+            targets.sort_by(|&a, &b| inn[b].cmp(&inn[a]).then(out[b].cmp(&out[a])).then(a.cmp(&b)));
+            for &v in &targets[..out[u]] {
+                inn[v] -= 1;
+                pairs.push((u, v));
+            }
+            out[u] = 0;
+        }
+
+        Ok(KleitmanWang { n, pairs })
+    }
+}
+
+impl Generator for KleitmanWang {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        expand(self.pairs.iter().cloned(), false)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+///
+/// Whether **degrees** is the degree sequence of a simple undirected graph,
+/// by the Erdős–Gallai theorem in O(n log n).
+///
+pub fn is_graphical(degrees: &[usize]) -> bool {
+    let n = degrees.len();
+    let mut d = degrees.to_vec();
+    d.sort_by(|a, b| b.cmp(a));
+
+    let mut prefix = vec![0; n + 1];
+    for i in 0..n {
+        prefix[i + 1] = prefix[i] + d[i];
+    }
+    if !prefix[n].is_multiple_of(2) {
+        return false;
+    }
+
+    // Number of degrees that are at least k
+    let mut p = n;
+    for k in 1..n + 1 {
+        while p > 0 && d[p - 1] < k {
+            p -= 1;
+        }
+
+        let capped = p.max(k);
+        let rhs = k * (k - 1) + k * (capped - k) + prefix[n] - prefix[capped];
+        if prefix[k] > rhs {
+            return false;
+        }
+    }
+
+    true
+}
+
+///
+/// Whether **out_degrees** and **in_degrees** are the degree sequences of a
+/// simple directed graph, by the Fulkerson–Chen–Anstee theorem.
+///
+pub fn is_digraphical(out_degrees: &[usize], in_degrees: &[usize]) -> bool {
+    let n = out_degrees.len();
+    if in_degrees.len() != n || out_degrees.iter().sum::<usize>() != in_degrees.iter().sum::<usize>() {
+        return false;
+    }
+    if out_degrees.iter().chain(in_degrees).any(|&d| d >= n) {
+        return false;
+    }
+
+    let mut pairs = out_degrees.iter().cloned().zip(in_degrees.iter().cloned()).collect::<Vec<(usize, usize)>>();
+    pairs.sort_by(|a, b| b.cmp(a));
+
+    let mut sorted_in = in_degrees.to_vec();
+    sorted_in.sort();
+
+    // Sum of min(b, k) over all in-degrees b: those below k count fully
+    let mut below = 0;
+    let mut below_sum = 0;
+    // In-degrees among the first k pairs, and how many of them are at least k
+    let mut counts = vec![0; n + 1];
+    let mut at_least = 0;
+    let mut lhs = 0;
+
+    for k in 1..n + 1 {
+        let (a, b) = pairs[k - 1];
+
+        while below < n && sorted_in[below] < k {
+            below_sum += sorted_in[below];
+            below += 1;
+        }
+
+        at_least -= counts[k - 1];
+        counts[b] += 1;
+        if b >= k {
+            at_least += 1;
+        }
+
+        lhs += a;
+        let rhs = below_sum + k * (n - below) - at_least;
+        if lhs > rhs {
+            return false;
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rand::Rng;
+
+    use Generator;
+    use StaticGraph;
+    use Edge;
+    use Error;
+
+    use representations::AdjacencyList;
+    use representations::Csr;
+    use generators::rng;
+    use generators::Erdos;
+    use generators::ConfigurationModel;
+    use generators::HavelHakimi;
+    use generators::KleitmanWang;
+    use generators::is_graphical;
+    use generators::is_digraphical;
+    use conformance::is_simple;
+    use conformance::is_symmetric;
+
+    fn degrees(edges: &[Edge], n: usize) -> (Vec<usize>, Vec<usize>) {
+        let mut out = vec![0; n];
+        let mut inn = vec![0; n];
+        for e in edges {
+            out[e.u()] += 1;
+            inn[e.v()] += 1;
+        }
+        (out, inn)
+    }
+
+    #[test]
+    fn graphical() {
+        assert!(is_graphical(&[]));
+        assert!(is_graphical(&[0, 0]));
+        assert!(is_graphical(&[3, 3, 3, 3]));
+        assert!(is_graphical(&[4, 1, 1, 1, 1]));
+        assert!(!is_graphical(&[1]));
+        assert!(!is_graphical(&[3, 3, 3, 1]));
+        assert!(!is_graphical(&[2, 0]));
+        assert!(!is_graphical(&[4, 4, 1, 1, 1, 1]));
+    }
+
+    #[test]
+    fn digraphical() {
+        assert!(is_digraphical(&[], &[]));
+        assert!(is_digraphical(&[1, 1], &[1, 1]));
+        assert!(is_digraphical(&[2, 0, 0], &[0, 1, 1]));
+        assert!(!is_digraphical(&[1], &[1]));
+        assert!(!is_digraphical(&[2, 0], &[0, 2]));
+        assert!(!is_digraphical(&[1, 1, 0], &[0, 0, 1]));
+    }
+
+    #[test]
+    fn havel_hakimi() {
+        let sequence = [3, 3, 2, 2, 2, 1, 1];
+        let hh = HavelHakimi::new(&sequence).unwrap();
+        let edges = hh.edges().collect::<Vec<Edge>>();
+        let graph = AdjacencyList::from_generator(&hh);
+
+        assert_eq!(degrees(&edges, 7).0, sequence.to_vec());
+        assert!(is_simple(&graph));
+        assert!(is_symmetric(&graph));
+        assert_eq!(HavelHakimi::new(&[3, 3, 3, 1]).err(), Some(Error::NotGraphical));
+        assert_eq!(HavelHakimi::new(&[1]).err(), Some(Error::NotGraphical));
+    }
+
+    #[test]
+    fn kleitman_wang() {
+        let out = [2, 2, 1, 0, 1];
+        let inn = [1, 1, 2, 2, 0];
+        let kw = KleitmanWang::new(&out, &inn).unwrap();
+        let edges = kw.edges().collect::<Vec<Edge>>();
+
+        assert_eq!(degrees(&edges, 5), (out.to_vec(), inn.to_vec()));
+        assert!(is_simple(&AdjacencyList::from_generator(&kw)));
+        assert_eq!(KleitmanWang::new(&[2, 0], &[0, 2]).err(), Some(Error::NotGraphical));
+        assert_eq!(KleitmanWang::new(&[1, 0], &[0, 0]).err(), Some(Error::NotGraphical));
+    }
+
+    #[test]
+    fn realizations_agree_with_tests() {
+        let mut rng = rng::seeded(1);
+
+        for _ in 0..500 {
+            let n = rng.gen_range(1, 9);
+            let out = (0..n).map(|_| rng.gen_range(0, n)).collect::<Vec<usize>>();
+            let mut inn = out.clone();
+            rng.shuffle(&mut inn);
+
+            match HavelHakimi::new(&out) {
+                Ok(hh) => {
+                    let edges = hh.edges().collect::<Vec<Edge>>();
+                    assert!(is_graphical(&out));
+                    assert_eq!(degrees(&edges, n).0, out);
+                    assert!(is_simple(&AdjacencyList::from_generator(&hh)));
+                }
+                Err(_) => assert!(!is_graphical(&out), "{:?}", out),
+            }
+
+            match KleitmanWang::new(&out, &inn) {
+                Ok(kw) => {
+                    let edges = kw.edges().collect::<Vec<Edge>>();
+                    assert!(is_digraphical(&out, &inn));
+                    assert_eq!(degrees(&edges, n), (out.clone(), inn.clone()));
+                    assert!(is_simple(&AdjacencyList::from_generator(&kw)));
+                }
+                Err(_) => assert!(!is_digraphical(&out, &inn), "{:?} {:?}", out, inn),
+            }
+        }
+    }
+
+    #[test]
+    fn configuration_model() {
+        let sequence = vec![5, 3, 3, 2, 2, 1, 0, 2];
+        let model = ConfigurationModel::new(sequence.clone()).unwrap().with_seed(2);
+        let edges = model.edges().collect::<Vec<Edge>>();
+
+        let (out, _) = degrees(&edges, 8);
+        let loops = edges.iter().filter(|e| e.u() == e.v()).map(|e| e.u()).collect::<Vec<_>>();
+        let realized = (0..8).map(|v| out[v] + loops.iter().filter(|&&u| u == v).count()).collect::<Vec<_>>();
+
+        assert_eq!(realized, sequence);
+        assert_eq!(model.edges().collect::<Vec<Edge>>(), edges);
+        assert_eq!(ConfigurationModel::new(vec![1, 2]).err(), Some(Error::NotGraphical));
+    }
+
+    #[test]
+    fn erased_configuration_model() {
+        let model = ConfigurationModel::new(vec![6; 20]).unwrap().without_loops().without_multi_edges().with_seed(3);
+        let graph = AdjacencyList::from_generator(&model);
+
+        assert!(is_simple(&graph));
+        assert!(is_symmetric(&graph));
+        assert!((0..20).all(|v| graph.neighbors(v).count() <= 6));
+    }
+
+    #[test]
+    fn directed_from_graph() {
+        let graph = Csr::from_generator(&Erdos::new(200, 0.03).with_seed(4));
+        let model = ConfigurationModel::from_graph(&graph).with_seed(5);
+        let edges = model.edges().collect::<Vec<Edge>>();
+        let original = graph.edges().collect::<Vec<Edge>>();
+
+        assert_eq!(degrees(&edges, 200), degrees(&original, 200));
+        assert_eq!(ConfigurationModel::directed(vec![1, 0], vec![0, 2]).err(), Some(Error::NotGraphical));
+    }
+}
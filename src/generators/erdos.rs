@@ -1,33 +1,293 @@
 extern crate rand;
 
 use Generator;
+use Node;
 use Edge;
 
 use self::rand::Rng;
 
+use super::rng;
+use super::util::expand;
+
+use std::collections::HashSet;
+use std::iter;
+
 ///
 /// Constructs an [Erdős–Rényi random
 /// graph](https://en.wikipedia.org/wiki/Erd%C5%91s%E2%80%93R%C3%A9nyi_model)
 /// with **n** nodes and an edge probability
 /// of **p**.
 ///
+/// Edges are drawn by geometric skipping (Batagelj and Brandes), which
+/// jumps directly to the next selected node pair and runs in O(n + m).
+///
+/// The graph is determined by its seed: every call to `edges()` yields the
+/// same edges, and generators with equal parameters and seeds yield equal
+/// graphs. `new` draws a random seed, use `with_seed` to fix it.
+///
 pub struct Erdos {
     n: usize,
     p: f64,
+    loops: bool,
+    undirected: bool,
+    seed: u64,
 }
 
 impl Erdos {
     pub fn new(n: usize, p: f64) -> Erdos {
-        Erdos { n, p }
+        Erdos { n, p, loops: true, undirected: false, seed: rng::random_seed() }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Erdos {
+        self.seed = seed;
+        self
+    }
+
+    ///
+    /// Excludes self-loops from the candidate node pairs.
+    ///
+    pub fn without_loops(mut self) -> Erdos {
+        self.loops = false;
+        self
+    }
+
+    ///
+    /// Selects each unordered node pair with probability **p** and
+    /// generates the selected edges in both directions.
+    ///
+    pub fn undirected(mut self) -> Erdos {
+        self.undirected = true;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
     }
 }
 
 impl Generator for Erdos {
-    fn edges<'a>(&'a self) -> Box<Iterator<Item=Edge> + 'a> {
-        let mut rng = rand::thread_rng();
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        Box::new(self.edge_chunks().unwrap().into_iter().flatten())
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+
+    ///
+    /// Splits the node pairs into consecutive ranges with about
+    /// `CHUNK_EDGES` expected edges each, every range with its own seed.
+    ///
+    fn edge_chunks<'a>(&'a self) -> Option<Vec<Box<dyn Iterator<Item=Edge> + Send + 'a>>> {
+        let total = Pairs::new(self.n, self.loops, self.undirected).total();
+        let count = (self.p.clamp(0.0, 1.0) * total as f64 / rng::CHUNK_EDGES).ceil().clamp(1.0, total.max(1) as f64) as u64;
+        let bound = |chunk: u64| (total as u128 * chunk as u128 / count as u128) as u64;
+
+        let chunks = (0..count).map(|chunk| {
+            let (start, end) = (bound(chunk), bound(chunk + 1));
+            let mut rng = rng::seeded_chunk(self.seed, chunk as usize);
+            let mut pairs = Pairs::new(self.n, self.loops, self.undirected);
+            pairs.seek(start);
+
+            let p = self.p;
+            let mut position = start;
+
+            let selected = iter::from_fn(move || {
+                let skip = rng::skip(&mut rng, p);
+                if skip >= (end - position) as f64 {
+                    return None;
+                }
+
+                position += skip as u64 + 1;
+                pairs.advance(skip as u64)
+            });
+
+            expand(selected, self.undirected)
+        }).collect();
+
+        Some(chunks)
+    }
+}
+
+///
+/// Constructs an Erdős–Rényi random graph with **n** nodes and exactly
+/// **m** distinct edges, chosen uniformly among all node pairs.
+///
+/// Supports the same options and seeding as `Erdos`. An undirected graph
+/// has **m** distinct unordered edges, each generated in both directions.
+///
+pub struct ErdosGnm {
+    n: usize,
+    m: usize,
+    loops: bool,
+    undirected: bool,
+    seed: u64,
+}
+
+impl ErdosGnm {
+    ///
+    /// Panics if there are fewer than **m** node pairs.
+    ///
+    pub fn new(n: usize, m: usize) -> ErdosGnm {
+        ErdosGnm { n, m, loops: true, undirected: false, seed: rng::random_seed() }.checked()
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> ErdosGnm {
+        self.seed = seed;
+        self
+    }
+
+    pub fn without_loops(mut self) -> ErdosGnm {
+        self.loops = false;
+        self.checked()
+    }
+
+    pub fn undirected(mut self) -> ErdosGnm {
+        self.undirected = true;
+        self.checked()
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn checked(self) -> ErdosGnm {
+        let total = Pairs::new(self.n, self.loops, self.undirected).total();
+        assert!(self.m as u64 <= total, "{} edges requested but only {} node pairs exist", self.m, total);
+        self
+    }
+}
+
+impl Generator for ErdosGnm {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let mut rng = rng::seeded(self.seed);
+        let mut pairs = Pairs::new(self.n, self.loops, self.undirected);
+
+        let chosen = sample(&mut rng, pairs.total(), self.m as u64);
+        let mut next = 0;
+
+        let selected = chosen.into_iter().filter_map(move |k| {
+            let skip = k - next;
+            next = k + 1;
+            pairs.advance(skip)
+        });
+
+        expand(selected, self.undirected)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+///
+/// Walks the candidate node pairs in row-major order. Row `u` holds the
+/// pairs `(u, v)` with any `v`, or with `v <= u` when undirected.
+///
+pub(super) struct Pairs {
+    n: usize,
+    loops: bool,
+    undirected: bool,
+    u: Node,
+    c: u64,
+}
+
+impl Pairs {
+    pub(super) fn new(n: usize, loops: bool, undirected: bool) -> Pairs {
+        Pairs { n, loops, undirected, u: 0, c: 0 }
+    }
+
+    fn row_len(&self, u: Node) -> u64 {
+        let len = if self.undirected { u as u64 + 1 } else { self.n as u64 };
+        if self.loops { len } else { len - 1 }
+    }
+
+    pub(super) fn total(&self) -> u64 {
+        let n = self.n as u64;
+        match (self.undirected, self.loops) {
+            (false, true) => n.checked_mul(n),
+            (false, false) => n.checked_mul(n.saturating_sub(1)),
+            (true, true) => n.checked_mul(n + 1).map(|t| t / 2),
+            (true, false) => n.checked_mul(n.saturating_sub(1)).map(|t| t / 2),
+        }.expect("too many node pairs")
+    }
+
+    ///
+    /// Moves to the candidate at **position** in the walk, so that the next
+    /// `advance(0)` returns it.
+    ///
+    pub(super) fn seek(&mut self, position: u64) {
+        // Last row starting at or before position
+        let (mut low, mut high) = (0, self.n);
+        while low < high {
+            let middle = low + (high - low).div_ceil(2);
+            if self.row_start(middle) <= position {
+                low = middle;
+            } else {
+                high = middle - 1;
+            }
+        }
+
+        self.u = low;
+        self.c = position - self.row_start(low);
+    }
+
+    fn row_start(&self, u: Node) -> u64 {
+        let (u, n) = (u as u64, self.n as u64);
+        match (self.undirected, self.loops) {
+            (false, true) => u * n,
+            (false, false) => u * n.saturating_sub(1),
+            (true, true) => u * (u + 1) / 2,
+            (true, false) => u * u.saturating_sub(1) / 2,
+        }
+    }
+
+    ///
+    /// Skips **skip** candidates and returns the one after them, or `None`
+    /// once all pairs are exhausted.
+    ///
+    pub(super) fn advance(&mut self, skip: u64) -> Option<(Node, Node)> {
+        self.c = self.c.saturating_add(skip);
+        while self.u < self.n && self.c >= self.row_len(self.u) {
+            self.c -= self.row_len(self.u);
+            self.u += 1;
+        }
+
+        if self.u >= self.n {
+            return None;
+        }
+
+        let c = self.c as usize;
+        let v = if !self.undirected && !self.loops && c >= self.u { c + 1 } else { c };
+        self.c += 1;
+
+        Some((self.u, v))
+    }
+}
 
-        Box::new((0..self.n).flat_map(move |u| (0..self.n).map(move |v| (u, v))).filter(move |_| rng.gen::<f64>() < self.p).map(|(u, v)| Edge::new(u,v)))
+///
+/// Chooses **m** distinct indices below **total** with Floyd's algorithm,
+/// sampling the complement when that is smaller. Returns them sorted.
+///
+fn sample<R: Rng>(rng: &mut R, total: u64, m: u64) -> Vec<u64> {
+    let k = m.min(total - m);
+    let mut set = HashSet::with_capacity(k as usize);
+
+    for j in total - k..total {
+        let t = rng.gen_range(0, j + 1);
+        if !set.insert(t) {
+            set.insert(j);
+        }
     }
+
+    let mut chosen = if k == m {
+        set.into_iter().collect::<Vec<u64>>()
+    } else {
+        (0..total).filter(|i| !set.contains(i)).collect()
+    };
+
+    chosen.sort();
+    chosen
 }
 
 #[cfg(test)]
@@ -36,9 +296,15 @@ mod tests {
 
     use representations::EdgeList;
     use representations::AdjacencyList;
+    use representations::Csr;
     use generators::Erdos;
+    use generators::ErdosGnm;
+    use Generator;
+    use Edge;
     use algorithms::breadth_first_search;
 
+    use std::collections::HashSet;
+
     #[test]
     fn creation_edgelist() {
         let erdos = Erdos::new(1000, 0.5);
@@ -84,4 +350,160 @@ mod tests {
         assert!(pred[0].is_some());
         assert_eq!(pred[0].unwrap(), 0);
     }
+
+    #[test]
+    fn edges_stable() {
+        let erdos = Erdos::new(100, 0.1);
+
+        assert_eq!(erdos.edges().collect::<Vec<Edge>>(), erdos.edges().collect::<Vec<Edge>>());
+    }
+
+    #[test]
+    fn seed_reproducible() {
+        let a = Erdos::new(100, 0.1).with_seed(7);
+        let b = Erdos::new(100, 0.1).with_seed(7);
+        let c = Erdos::new(100, 0.1).with_seed(8);
+
+        assert_eq!(a.seed(), 7);
+        assert_eq!(a.edges().collect::<Vec<Edge>>(), b.edges().collect::<Vec<Edge>>());
+        assert!(a.edges().collect::<Vec<Edge>>() != c.edges().collect::<Vec<Edge>>());
+    }
+
+    #[test]
+    fn seed_reused() {
+        let a = Erdos::new(100, 0.1);
+        let b = Erdos::new(100, 0.1).with_seed(a.seed());
+
+        assert_eq!(a.edges().collect::<Vec<Edge>>(), b.edges().collect::<Vec<Edge>>());
+    }
+
+    #[test]
+    fn csr_consistent() {
+        let erdos = Erdos::new(200, 0.05).with_seed(3);
+        let graph = Csr::from_generator(&erdos);
+
+        assert_eq!(graph.edges().collect::<Vec<Edge>>(), erdos.edges().collect::<Vec<Edge>>());
+    }
+
+    #[test]
+    fn extreme_probabilities() {
+        assert_eq!(Erdos::new(30, 0.0).edges().count(), 0);
+        assert_eq!(Erdos::new(30, 1.0).edges().count(), 900);
+        assert_eq!(Erdos::new(30, 1.0).without_loops().edges().count(), 870);
+        assert_eq!(Erdos::new(30, 1.0).undirected().edges().count(), 900);
+        assert_eq!(Erdos::new(30, 1.0).undirected().without_loops().edges().count(), 870);
+        assert_eq!(Erdos::new(0, 1.0).edges().count(), 0);
+        assert_eq!(Erdos::new(1, 1.0).without_loops().edges().count(), 0);
+    }
+
+    #[test]
+    fn complete_order() {
+        let edges = Erdos::new(3, 1.0).without_loops().edges().collect::<Vec<Edge>>();
+
+        assert_eq!(edges, vec![Edge::new(0, 1), Edge::new(0, 2), Edge::new(1, 0), Edge::new(1, 2), Edge::new(2, 0), Edge::new(2, 1)]);
+    }
+
+    #[test]
+    fn without_loops() {
+        let erdos = Erdos::new(200, 0.3).without_loops().with_seed(1);
+
+        assert!(erdos.edges().all(|e| e.u() != e.v()));
+    }
+
+    #[test]
+    fn undirected() {
+        let erdos = Erdos::new(200, 0.05).undirected().with_seed(2);
+        let graph = AdjacencyList::from_generator(&erdos);
+
+        assert!(graph.edges().all(|e| graph.has_edge(e.v(), e.u())));
+    }
+
+    #[test]
+    fn sparse() {
+        let erdos = Erdos::new(1_000_000, 1e-6).with_seed(5);
+        let count = erdos.edges().count();
+
+        assert!(count > 900_000);
+        assert!(count < 1_100_000);
+        assert!(erdos.edges().all(|e| e.u() < 1_000_000 && e.v() < 1_000_000));
+    }
+
+    #[test]
+    fn chunks() {
+        let variants = vec![
+            Erdos::new(1000, 0.14).with_seed(8),
+            Erdos::new(1000, 0.14).without_loops().with_seed(8),
+            Erdos::new(1000, 0.14).undirected().with_seed(8),
+            Erdos::new(1000, 0.14).undirected().without_loops().with_seed(8),
+        ];
+
+        for erdos in &variants {
+            let edges = erdos.edges().collect::<Vec<Edge>>();
+            let pairs = edges.iter().map(|e| (e.u(), e.v())).collect::<HashSet<_>>();
+
+            assert!(erdos.edge_chunks().unwrap().len() > 1);
+            assert_eq!(pairs.len(), edges.len());
+            for &threads in &[1, 3, 8] {
+                assert_eq!(erdos.edges_parallel(threads).concat(), edges);
+            }
+        }
+    }
+
+    #[test]
+    fn chunks_dense() {
+        // Every pair is selected, so chunk boundaries must not lose or repeat any
+        let erdos = Erdos::new(300, 1.0).undirected().without_loops().with_seed(9);
+        assert_eq!(erdos.edges_parallel(4).concat().len(), 300 * 299);
+    }
+
+    #[test]
+    fn isolated_nodes() {
+        let graph = Csr::from_generator(&Erdos::new(50, 0.0));
+
+        assert_eq!(graph.num_nodes(), 50);
+    }
+
+    fn distinct(edges: &[Edge]) -> usize {
+        let mut edges = edges.to_vec();
+        edges.sort();
+        edges.dedup();
+        edges.len()
+    }
+
+    #[test]
+    fn gnm_exact() {
+        for &m in &[0, 1, 100, 2_000, 9_999, 10_000] {
+            let edges = ErdosGnm::new(100, m).with_seed(m as u64).edges().collect::<Vec<Edge>>();
+
+            assert_eq!(edges.len(), m);
+            assert_eq!(distinct(&edges), m);
+            assert!(edges.iter().all(|e| e.u() < 100 && e.v() < 100));
+        }
+    }
+
+    #[test]
+    fn gnm_undirected_without_loops() {
+        let gnm = ErdosGnm::new(100, 4_000).undirected().without_loops().with_seed(9);
+        let edges = gnm.edges().collect::<Vec<Edge>>();
+        let graph = AdjacencyList::from_generator(&gnm);
+
+        assert_eq!(edges.len(), 8_000);
+        assert_eq!(distinct(&edges), 8_000);
+        assert!(edges.iter().all(|e| e.u() != e.v() && graph.has_edge(e.v(), e.u())));
+    }
+
+    #[test]
+    fn gnm_reproducible() {
+        let a = ErdosGnm::new(1000, 5000).with_seed(4);
+        let b = ErdosGnm::new(1000, 5000).with_seed(4);
+
+        assert_eq!(a.edges().collect::<Vec<Edge>>(), a.edges().collect::<Vec<Edge>>());
+        assert_eq!(a.edges().collect::<Vec<Edge>>(), b.edges().collect::<Vec<Edge>>());
+    }
+
+    #[test]
+    #[should_panic]
+    fn gnm_too_many_edges() {
+        ErdosGnm::new(10, 50).undirected().without_loops();
+    }
 }
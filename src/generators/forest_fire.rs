@@ -0,0 +1,177 @@
+extern crate rand;
+
+use Generator;
+use Node;
+use Edge;
+
+use self::rand::Rng;
+
+use super::rng;
+use super::rng::GeneratorRng;
+use super::util::expand;
+
+use std::collections::VecDeque;
+
+///
+/// Constructs a [forest-fire](https://doi.org/10.1145/1081870.1081893)
+/// graph with **n** nodes. Every new node links to a uniformly chosen
+/// ambassador and "burns" through the graph from it: each burning node sets
+/// fire to a geometric number of its unburned out-neighbors, with mean
+/// `forward / (1 - forward)`, and of its in-neighbors, with mean
+/// `backward / (1 - backward)`. The new node links to every burned node.
+///
+/// Edges point from each new node to older ones and are generated in node
+/// arrival order, so they double as a temporal stream. The model shows
+/// densification and shrinking diameters for suitable probabilities.
+///
+pub struct ForestFire {
+    n: usize,
+    forward: f64,
+    backward: f64,
+    undirected: bool,
+    seed: u64,
+}
+
+impl ForestFire {
+    pub fn new(n: usize, forward: f64, backward: f64) -> ForestFire {
+        assert!((0.0..1.0).contains(&forward), "forward burning probability must be in [0, 1)");
+        assert!((0.0..1.0).contains(&backward), "backward burning probability must be in [0, 1)");
+
+        ForestFire { n, forward, backward, undirected: false, seed: rng::random_seed() }
+    }
+
+    pub fn undirected(mut self) -> ForestFire {
+        self.undirected = true;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> ForestFire {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Generator for ForestFire {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let mut rng = rng::seeded(self.seed);
+        let n = self.n;
+
+        let mut out_neighbors = vec![vec![]; n];
+        let mut in_neighbors = vec![vec![]; n];
+        let mut burned_by = vec![usize::MAX; n];
+        let mut pairs = vec![];
+
+        for v in 1..n {
+            let ambassador = rng.gen_range(0, v);
+            burned_by[ambassador] = v;
+
+            let mut fire = VecDeque::new();
+            fire.push_back(ambassador);
+            let mut burned = vec![];
+
+            while let Some(u) = fire.pop_front() {
+                burned.push(u);
+
+                let forward = rng::skip(&mut rng, 1.0 - self.forward) as usize;
+                let backward = rng::skip(&mut rng, 1.0 - self.backward) as usize;
+                for w in spread(&mut rng, &out_neighbors[u], &burned_by, v, forward).into_iter().chain(spread(&mut rng, &in_neighbors[u], &burned_by, v, backward)) {
+                    if burned_by[w] != v {
+                        burned_by[w] = v;
+                        fire.push_back(w);
+                    }
+                }
+            }
+
+            for u in burned {
+                out_neighbors[v].push(u);
+                in_neighbors[u].push(v);
+                pairs.push((v, u));
+            }
+        }
+
+        expand(pairs.into_iter(), self.undirected)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+///
+/// Up to **count** random **neighbors** not yet burned by node **v**.
+///
+fn spread(rng: &mut GeneratorRng, neighbors: &[Node], burned_by: &[usize], v: Node, count: usize) -> Vec<Node> {
+    if count == 0 {
+        return vec![];
+    }
+
+    let mut unburned = neighbors.iter().cloned().filter(|&w| burned_by[w] != v).collect::<Vec<Node>>();
+    let count = count.min(unburned.len());
+    for i in 0..count {
+        let j = rng.gen_range(i, unburned.len());
+        unburned.swap(i, j);
+    }
+
+    unburned.truncate(count);
+    unburned
+}
+
+#[cfg(test)]
+mod tests {
+    use Generator;
+    use StaticGraph;
+    use Edge;
+
+    use representations::AdjacencyList;
+    use generators::ForestFire;
+    use conformance::is_simple;
+
+    #[test]
+    fn arrival_order() {
+        let edges = ForestFire::new(2000, 0.35, 0.3).with_seed(1).edges().collect::<Vec<Edge>>();
+        let graph = AdjacencyList::from_generator(&ForestFire::new(2000, 0.35, 0.3).with_seed(1));
+
+        assert!(edges.iter().all(|e| e.u() > e.v()));
+        assert!(edges.windows(2).all(|w| w[0].u() <= w[1].u()));
+        assert!((1..2000).all(|v| graph.neighbors(v).count() >= 1));
+        assert_eq!(graph.neighbors(0).count(), 0);
+    }
+
+    #[test]
+    fn simple() {
+        let graph = AdjacencyList::from_generator(&ForestFire::new(1000, 0.4, 0.3).with_seed(2));
+        assert!(is_simple(&graph));
+    }
+
+    #[test]
+    fn no_burning() {
+        // Only the ambassador is linked, which gives a random recursive tree
+        let graph = AdjacencyList::from_generator(&ForestFire::new(500, 0.0, 0.0).with_seed(3));
+
+        assert_eq!(graph.num_edges(), 499);
+        assert!((1..500).all(|v| graph.neighbors(v).count() == 1));
+    }
+
+    #[test]
+    fn densification() {
+        let edges = ForestFire::new(8000, 0.37, 0.32).with_seed(4).edges().collect::<Vec<Edge>>();
+        let density = |n: usize| edges.iter().filter(|e| e.u() < n).count() as f64 / n as f64;
+
+        assert!(density(1000) < density(4000));
+        assert!(density(4000) < density(8000));
+    }
+
+    #[test]
+    fn undirected() {
+        let fire = ForestFire::new(300, 0.3, 0.2).with_seed(5);
+        let directed = fire.edges().count();
+        let graph = AdjacencyList::from_generator(&fire.undirected());
+
+        assert_eq!(graph.num_edges(), 2 * directed);
+        assert!(graph.edges().all(|e| graph.has_edge(e.v(), e.u())));
+    }
+}
@@ -0,0 +1,365 @@
+extern crate rand;
+
+use Generator;
+use Node;
+use Edge;
+
+use self::rand::Rng;
+
+use super::rng;
+use super::util::expand;
+
+///
+/// Constructs a [random geometric
+/// graph](https://en.wikipedia.org/wiki/Random_geometric_graph) on **n**
+/// points drawn uniformly from the unit cube of the given **dimensions**.
+/// Two nodes are adjacent when their Euclidean distance is below **radius**,
+/// and every edge is generated in both directions.
+///
+/// Points are bucketed in a grid of cells at least **radius** wide, so only
+/// neighboring cells are compared instead of all pairs.
+///
+pub struct RandomGeometric {
+    n: usize,
+    dimensions: usize,
+    radius: f64,
+    seed: u64,
+}
+
+impl RandomGeometric {
+    pub fn new(n: usize, dimensions: usize, radius: f64) -> RandomGeometric {
+        assert!(dimensions > 0, "points need at least one dimension");
+        assert!(radius >= 0.0, "radius must not be negative");
+
+        RandomGeometric { n, dimensions, radius, seed: rng::random_seed() }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> RandomGeometric {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    ///
+    /// Coordinates of every node, indexed by node.
+    ///
+    pub fn points(&self) -> Vec<Vec<f64>> {
+        let mut rng = rng::seeded(self.seed);
+        (0..self.n).map(|_| (0..self.dimensions).map(|_| rng.gen::<f64>()).collect()).collect()
+    }
+}
+
+impl Generator for RandomGeometric {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let points = self.points();
+        let per_side = (self.n as f64).powf(1.0 / self.dimensions as f64).min(1.0 / self.radius);
+        let index = SpatialIndex::new(&points, per_side as usize, self.radius);
+        let limit = self.radius * self.radius;
+
+        let mut pairs = vec![];
+        for (u, point) in points.iter().enumerate() {
+            index.visit(&index.cell(point), 1, false, |bucket| {
+                for &v in bucket {
+                    if v > u && distance(point, &points[v]) < limit {
+                        pairs.push((u, v));
+                    }
+                }
+            });
+        }
+
+        pairs.sort();
+        expand(pairs.into_iter(), true)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+///
+/// Constructs the [k-nearest-neighbor
+/// graph](https://en.wikipedia.org/wiki/Nearest_neighbor_graph) of a set of
+/// **points**, one node per point. Each node has edges to the **k** points
+/// closest to it by Euclidean distance, ties broken by the lower node. When
+/// undirected, the edges are symmetrized so nodes may end up with more than
+/// **k** neighbors.
+///
+/// Neighbors are found by searching rings of grid cells outward from each
+/// point until no unsearched cell can hold a closer one.
+///
+pub struct NearestNeighbors {
+    points: Vec<Vec<f64>>,
+    k: usize,
+    undirected: bool,
+}
+
+impl NearestNeighbors {
+    pub fn new(points: Vec<Vec<f64>>, k: usize) -> NearestNeighbors {
+        assert!(k < points.len() || k == 0, "k must be smaller than the number of points");
+        assert!(points.iter().all(|p| p.len() == points[0].len()), "points must have the same dimensions");
+        assert!(points.iter().flatten().all(|x| x.is_finite()), "coordinates must be finite");
+
+        NearestNeighbors { points, k, undirected: false }
+    }
+
+    pub fn undirected(mut self) -> NearestNeighbors {
+        self.undirected = true;
+        self
+    }
+
+    pub fn points(&self) -> &[Vec<f64>] {
+        &self.points
+    }
+
+    ///
+    /// The **k** nearest neighbors of **node**, closest first.
+    ///
+    pub fn neighbors(&self, node: Node) -> Vec<Node> {
+        self.nearest(&self.index(), node)
+    }
+
+    fn index(&self) -> SpatialIndex {
+        let dimensions = self.points.first().map_or(1, |p| p.len().max(1));
+        let per_side = (self.points.len() as f64 / self.k.max(1) as f64).powf(1.0 / dimensions as f64);
+        SpatialIndex::new(&self.points, per_side as usize, 0.0)
+    }
+
+    fn nearest(&self, index: &SpatialIndex, u: Node) -> Vec<Node> {
+        let point = &self.points[u];
+        let cell = index.cell(point);
+        let mut candidates = vec![];
+
+        for reach in 0..index.cells + 1 {
+            index.visit(&cell, reach, true, |bucket| {
+                candidates.extend(bucket.iter().filter(|&&v| v != u).map(|&v| (distance(point, &self.points[v]), v)));
+            });
+
+            candidates.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            candidates.truncate(self.k);
+
+            // Points in unsearched cells are at least `reach` cells away
+            let bound = reach as f64 * index.size;
+            if candidates.len() == self.k && candidates.last().is_none_or(|&(d, _)| d < bound * bound) {
+                break;
+            }
+        }
+
+        candidates.into_iter().map(|(_, v)| v).collect()
+    }
+}
+
+impl Generator for NearestNeighbors {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let index = self.index();
+        let mut pairs = (0..self.points.len()).flat_map(|u| self.nearest(&index, u).into_iter().map(move |v| (u, v))).collect::<Vec<(Node, Node)>>();
+
+        if self.undirected {
+            pairs = pairs.into_iter().map(|(u, v)| (u.min(v), u.max(v))).collect();
+            pairs.sort();
+            pairs.dedup();
+        }
+
+        expand(pairs.into_iter(), self.undirected)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.points.len())
+    }
+}
+
+///
+/// Squared Euclidean distance between two points.
+///
+fn distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+///
+/// Buckets points into a grid of cubic cells, up to **cells** along each
+/// side, covering their bounding box. Cells are at least **width** wide.
+///
+struct SpatialIndex {
+    cells: usize,
+    lower: Vec<f64>,
+    size: f64,
+    buckets: Vec<Vec<Node>>,
+}
+
+impl SpatialIndex {
+    fn new(points: &[Vec<f64>], cells: usize, width: f64) -> SpatialIndex {
+        let dimensions = points.first().map_or(0, |p| p.len());
+        let lower = (0..dimensions).map(|i| points.iter().map(|p| p[i]).fold(f64::INFINITY, f64::min)).collect::<Vec<f64>>();
+        let extent = (0..dimensions).map(|i| points.iter().map(|p| p[i] - lower[i]).fold(0.0, f64::max)).fold(0.0, f64::max);
+
+        // Never more cells than points
+        let mut cells = cells.max(1);
+        while cells > 1 && (cells as f64).powi(dimensions as i32) > points.len() as f64 {
+            cells -= 1;
+        }
+
+        let mut index = SpatialIndex {
+            cells,
+            lower,
+            size: if extent > 0.0 { (extent / cells as f64).max(width) } else { 1.0 },
+            buckets: vec![vec![]; cells.pow(dimensions as u32)],
+        };
+
+        for (v, point) in points.iter().enumerate() {
+            let bucket = index.bucket(&index.cell(point));
+            index.buckets[bucket].push(v);
+        }
+
+        index
+    }
+
+    fn cell(&self, point: &[f64]) -> Vec<usize> {
+        point.iter().zip(&self.lower).map(|(x, l)| (((x - l) / self.size) as usize).min(self.cells - 1)).collect()
+    }
+
+    fn bucket(&self, cell: &[usize]) -> usize {
+        cell.iter().fold(0, |b, &c| b * self.cells + c)
+    }
+
+    ///
+    /// Calls **f** on the bucket of every cell within **reach** cells of
+    /// **center** along each axis, or exactly **reach** away if **ring**.
+    ///
+    fn visit<F: FnMut(&[Node])>(&self, center: &[usize], reach: usize, ring: bool, mut f: F) {
+        let low = center.iter().map(|&c| c.saturating_sub(reach)).collect::<Vec<usize>>();
+        let high = center.iter().map(|&c| (c + reach).min(self.cells - 1)).collect::<Vec<usize>>();
+        let mut cell = low.clone();
+
+        loop {
+            let away = cell.iter().zip(center).map(|(&a, &b)| a.max(b) - a.min(b)).max().unwrap_or(0);
+            if !ring || away == reach {
+                f(&self.buckets[self.bucket(&cell)]);
+            }
+
+            // Odometer over the box, last axis fastest
+            let mut axis = cell.len();
+            loop {
+                if axis == 0 {
+                    return;
+                }
+                axis -= 1;
+
+                if cell[axis] < high[axis] {
+                    cell[axis] += 1;
+                    break;
+                }
+                cell[axis] = low[axis];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use Generator;
+    use StaticGraph;
+    use Edge;
+
+    use representations::AdjacencyList;
+    use generators::RandomGeometric;
+    use generators::NearestNeighbors;
+
+    use super::distance;
+
+    fn brute_force_knn(points: &[Vec<f64>], u: usize, k: usize) -> Vec<usize> {
+        let mut others = (0..points.len()).filter(|&v| v != u).map(|v| (distance(&points[u], &points[v]), v)).collect::<Vec<_>>();
+        others.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        others.into_iter().take(k).map(|(_, v)| v).collect()
+    }
+
+    #[test]
+    fn geometric_against_all_pairs() {
+        for &(n, dimensions, radius) in &[(500, 2, 0.08), (300, 3, 0.2), (200, 1, 0.01), (50, 4, 0.9)] {
+            let generator = RandomGeometric::new(n, dimensions, radius).with_seed(n as u64);
+            let points = generator.points();
+
+            let mut expected = vec![];
+            for u in 0..n {
+                for v in 0..n {
+                    if u != v && distance(&points[u], &points[v]) < radius * radius {
+                        expected.push(Edge::new(u, v));
+                    }
+                }
+            }
+
+            let mut edges = generator.edges().collect::<Vec<Edge>>();
+            edges.sort_by_key(|e| (e.u(), e.v()));
+
+            assert_eq!(edges, expected);
+        }
+    }
+
+    #[test]
+    fn geometric_points() {
+        let generator = RandomGeometric::new(100, 3, 0.1).with_seed(1);
+        let points = generator.points();
+
+        assert_eq!(points.len(), 100);
+        assert!(points.iter().all(|p| p.len() == 3 && p.iter().all(|&x| (0.0..1.0).contains(&x))));
+        assert_eq!(points, generator.points());
+    }
+
+    #[test]
+    fn geometric_extremes() {
+        let empty = AdjacencyList::from_generator(&RandomGeometric::new(100, 2, 0.0).with_seed(2));
+        let complete = AdjacencyList::from_generator(&RandomGeometric::new(30, 2, 1.5).with_seed(2));
+
+        assert_eq!(empty.num_nodes(), 100);
+        assert_eq!(empty.num_edges(), 0);
+        assert_eq!(complete.num_edges(), 30 * 29);
+        assert_eq!(RandomGeometric::new(0, 2, 0.5).edges().count(), 0);
+    }
+
+    #[test]
+    fn knn_against_brute_force() {
+        for &(n, k) in &[(400, 5), (100, 1), (20, 19)] {
+            let points = RandomGeometric::new(n, 2, 0.1).with_seed(k as u64).points();
+            let knn = NearestNeighbors::new(points.clone(), k);
+
+            for u in 0..n {
+                assert_eq!(knn.neighbors(u), brute_force_knn(&points, u, k));
+            }
+
+            let graph = AdjacencyList::from_generator(&knn);
+            assert!((0..n).all(|u| graph.neighbors(u).count() == k));
+        }
+    }
+
+    #[test]
+    fn knn_skewed_points() {
+        // Clustered, collinear and duplicated points stress the grid
+        let mut points = (0..200).map(|i| vec![(i as f64).powi(3), 7.0, -(i as f64)]).collect::<Vec<_>>();
+        points.push(vec![0.0, 7.0, 0.0]);
+        points.push(vec![0.0, 7.0, 0.0]);
+        let knn = NearestNeighbors::new(points.clone(), 4);
+
+        for u in 0..points.len() {
+            assert_eq!(knn.neighbors(u), brute_force_knn(&points, u, 4));
+        }
+    }
+
+    #[test]
+    fn knn_undirected() {
+        let points = vec![vec![0.0], vec![1.0], vec![2.0], vec![10.0]];
+        let graph = AdjacencyList::from_generator(&NearestNeighbors::new(points, 1).undirected());
+
+        assert_eq!(graph.num_edges(), 6);
+        assert!(graph.has_edge(0, 1) && graph.has_edge(1, 0));
+        assert!(graph.has_edge(2, 3) && graph.has_edge(3, 2));
+        assert!(!graph.has_edge(0, 3));
+        assert_eq!(graph.neighbors(2).count(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn nan_coordinates() {
+        NearestNeighbors::new(vec![vec![0.0], vec![f64::NAN], vec![1.0]], 1);
+    }
+}
@@ -0,0 +1,282 @@
+extern crate rand;
+
+use Generator;
+use Edge;
+
+use self::rand::Rng;
+
+use super::rng;
+use super::util::expand;
+
+use std::f64::consts::PI;
+
+///
+/// Constructs a [random hyperbolic
+/// graph](https://en.wikipedia.org/wiki/Hyperbolic_geometric_graph) with **n**
+/// nodes placed in a hyperbolic disk. The disk radius is chosen so that the
+/// average degree is close to **average_degree**, and the radial density
+/// gives a power-law degree distribution with the given **exponent**, which
+/// must be above 2. At **temperature** zero nodes closer than the disk
+/// radius `R` are connected; above zero, nodes at distance `d` are connected
+/// with probability `1 / (1 + e^((d - R) / 2T))`. Temperatures must be below
+/// 1. Each edge is generated in both directions.
+///
+/// Nodes are split into radial bands and sorted by angle within each, so
+/// only an angular window of every band is searched for each node
+/// (von Looz et al.), with candidates at nonzero temperature sampled by
+/// skipping over an upper bound of the connection probability.
+///
+pub struct Hyperbolic {
+    n: usize,
+    average_degree: f64,
+    exponent: f64,
+    temperature: f64,
+    seed: u64,
+}
+
+impl Hyperbolic {
+    pub fn new(n: usize, average_degree: f64, exponent: f64, temperature: f64) -> Hyperbolic {
+        assert!(average_degree > 0.0 && average_degree < n as f64, "average degree must be between 0 and n");
+        assert!(exponent > 2.0, "power-law exponent must be above 2");
+        assert!((0.0..1.0).contains(&temperature), "temperature must be in [0, 1)");
+
+        Hyperbolic { n, average_degree, exponent, temperature, seed: rng::random_seed() }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Hyperbolic {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    ///
+    /// Radius `R` of the disk the nodes are placed in.
+    ///
+    pub fn radius(&self) -> f64 {
+        let alpha = self.alpha();
+        let xi = alpha / (alpha - 0.5);
+        let n = self.n as f64;
+        let factor = if self.temperature > 0.0 { PI * self.temperature / (PI * self.temperature).sin() } else { 1.0 };
+
+        // Expected average degree for radius r, decreasing beyond small radii
+        let correction = PI / 4.0 / alpha / alpha - (PI - 1.0) / alpha + PI - 2.0;
+        let degree = |r: f64| 2.0 / PI * xi * xi * n * factor * ((-r / 2.0).exp() + (-alpha * r).exp() * (alpha * r / 2.0 * correction - 1.0));
+
+        let (mut low, mut high) = (1.0, 2.0 * n.ln() + 40.0);
+        for _ in 0..100 {
+            let middle = (low + high) / 2.0;
+            if degree(middle) > self.average_degree {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+
+        (low + high) / 2.0
+    }
+
+    ///
+    /// Polar coordinates `(radius, angle)` of every node, indexed by node.
+    ///
+    pub fn coordinates(&self) -> Vec<(f64, f64)> {
+        self.sample(&mut rng::seeded(self.seed))
+    }
+
+    fn sample(&self, rng: &mut rng::GeneratorRng) -> Vec<(f64, f64)> {
+        let alpha = self.alpha();
+        let scale = (alpha * self.radius()).cosh() - 1.0;
+
+        (0..self.n).map(|_| {
+            let angle = rng.gen::<f64>() * 2.0 * PI;
+            let radius = (1.0 + scale * rng.gen::<f64>()).acosh() / alpha;
+            (radius, angle)
+        }).collect()
+    }
+
+    fn alpha(&self) -> f64 {
+        (self.exponent - 1.0) / 2.0
+    }
+
+    fn probability(&self, distance: f64, radius: f64) -> f64 {
+        if self.temperature > 0.0 {
+            1.0 / (1.0 + ((distance - radius) / (2.0 * self.temperature)).exp())
+        } else if distance < radius {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Generator for Hyperbolic {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let radius = self.radius();
+        let mut rng = rng::seeded(self.seed);
+        let points = self.sample(&mut rng);
+
+        // An inner band up to R / 2, whose nodes are all connected at zero
+        // temperature, and about log n equal bands beyond it
+        let count = ((self.n as f64).log2() as usize).max(2);
+        let mut bounds = (0..count).map(|i| radius / 2.0 * (1.0 + i as f64 / (count - 1) as f64)).collect::<Vec<f64>>();
+        bounds.insert(0, 0.0);
+
+        let band = |r: f64| bounds.iter().rposition(|&b| b <= r).unwrap().min(count - 1);
+        let mut bands = vec![vec![]; count];
+        for (v, &(r, angle)) in points.iter().enumerate() {
+            bands[band(r)].push((angle, v));
+        }
+        for nodes in &mut bands {
+            nodes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        }
+
+        let mut pairs = vec![];
+        for (u, &(r, angle)) in points.iter().enumerate() {
+            for (j, nodes) in bands.iter().enumerate().skip(band(r)) {
+                // Only pairs with v further out, so every pair is seen once
+                let low = bounds[j].max(r);
+                let m = nodes.len();
+                let start = nodes.partition_point(|&(a, _)| a < angle);
+                let opposite = angle + PI;
+                let half = if opposite < 2.0 * PI {
+                    nodes.partition_point(|&(a, _)| a < opposite) - start
+                } else {
+                    m - start + nodes.partition_point(|&(a, _)| a < opposite - 2.0 * PI)
+                };
+
+                // Walk clockwise through the half circle, then counterclockwise
+                for &(steps, clockwise) in &[(half, true), (m - half, false)] {
+                    let at = |s: usize| if clockwise { nodes[(start + s) % m] } else { nodes[(start + 2 * m - 1 - s) % m] };
+
+                    let mut s = 0;
+                    while s < steps {
+                        // Angles only grow apart along the walk, so p bounds what follows
+                        let p = self.probability(distance(r, low, at(s).0 - angle), radius);
+                        if p <= 0.0 {
+                            break;
+                        }
+
+                        s += rng::skip(&mut rng, p).min(steps as f64) as usize;
+                        if s >= steps {
+                            break;
+                        }
+
+                        let (theta, v) = at(s);
+                        let outer = points[v].0 > r || (points[v].0 == r && v > u);
+                        if outer && rng.gen::<f64>() < self.probability(distance(r, points[v].0, theta - angle), radius) / p {
+                            pairs.push((u, v));
+                        }
+                        s += 1;
+                    }
+                }
+            }
+        }
+
+        expand(pairs.into_iter(), true)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+///
+/// Hyperbolic distance between points at radii **a** and **b** whose angles
+/// differ by **angle**, in a form that stays accurate for nearby points.
+///
+fn distance(a: f64, b: f64, angle: f64) -> f64 {
+    let half = (angle / 2.0).sin();
+    ((a - b).cosh() + 2.0 * half * half * a.sinh() * b.sinh()).acosh()
+}
+
+#[cfg(test)]
+mod tests {
+    use Generator;
+    use StaticGraph;
+    use Edge;
+
+    use representations::AdjacencyList;
+    use generators::Hyperbolic;
+
+    use super::distance;
+
+    use std::f64::consts::PI;
+
+    fn average_degree(graph: &AdjacencyList) -> f64 {
+        graph.num_edges() as f64 / graph.num_nodes() as f64
+    }
+
+    #[test]
+    fn threshold_against_all_pairs() {
+        let generator = Hyperbolic::new(600, 8.0, 2.5, 0.0).with_seed(1);
+        let points = generator.coordinates();
+        let radius = generator.radius();
+
+        let mut expected = vec![];
+        for (u, &(a, alpha)) in points.iter().enumerate() {
+            for (v, &(b, beta)) in points.iter().enumerate() {
+                if u != v && distance(a, b, alpha - beta) < radius {
+                    expected.push(Edge::new(u, v));
+                }
+            }
+        }
+
+        let mut edges = generator.edges().collect::<Vec<Edge>>();
+        edges.sort_by_key(|e| (e.u(), e.v()));
+
+        assert_eq!(edges, expected);
+    }
+
+    #[test]
+    fn temperature_against_expectation() {
+        let mut expected = 0.0;
+        let mut observed = 0.0;
+
+        for seed in 0..20 {
+            let generator = Hyperbolic::new(500, 6.0, 2.7, 0.6).with_seed(seed);
+            let points = generator.coordinates();
+            let radius = generator.radius();
+
+            for (u, &(a, alpha)) in points.iter().enumerate() {
+                for &(b, beta) in &points[u + 1..] {
+                    expected += generator.probability(distance(a, b, alpha - beta), radius);
+                }
+            }
+            observed += generator.edges().count() as f64 / 2.0;
+        }
+
+        assert!((observed - expected).abs() < 4.0 * expected.sqrt());
+    }
+
+    #[test]
+    fn degrees() {
+        for &(exponent, temperature) in &[(2.5, 0.0), (3.0, 0.0), (2.5, 0.5)] {
+            let graph = AdjacencyList::from_generator(&Hyperbolic::new(5000, 10.0, exponent, temperature).with_seed(2));
+            let max_degree = (0..5000).map(|v| graph.neighbors(v).count()).max().unwrap();
+
+            assert!(average_degree(&graph) > 8.0 && average_degree(&graph) < 12.0);
+            assert!(max_degree > 100);
+        }
+    }
+
+    #[test]
+    fn coordinates() {
+        let generator = Hyperbolic::new(1000, 5.0, 2.2, 0.3).with_seed(3);
+        let radius = generator.radius();
+        let points = generator.coordinates();
+
+        assert_eq!(points.len(), 1000);
+        assert!(points.iter().all(|&(r, angle)| (0.0..=radius).contains(&r) && (0.0..2.0 * PI).contains(&angle)));
+        assert_eq!(points, generator.coordinates());
+        assert_eq!(generator.edges().collect::<Vec<Edge>>(), generator.edges().collect::<Vec<Edge>>());
+    }
+
+    #[test]
+    fn hyperbolic_distance() {
+        assert!((distance(1.0, 2.0, 0.0) - 1.0).abs() < 1e-9);
+        assert!((distance(1.5, 2.0, PI) - 3.5).abs() < 1e-9);
+        assert!((distance(20.0, 20.0, 1e-9) - distance(20.0, 20.0, -1e-9)).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,362 @@
+extern crate rand;
+
+use Generator;
+use Node;
+use Edge;
+
+use self::rand::Rng;
+
+use super::rng;
+use super::rng::GeneratorRng;
+use super::util::expand;
+
+use std::sync::Arc;
+
+///
+/// Constructs an [R-MAT](https://doi.org/10.1137/1.9781611972740.43) graph
+/// as in the [Graph500](https://graph500.org) benchmark, with `2^scale` nodes
+/// and `edge_factor * 2^scale` edges. Each edge recursively picks one of the
+/// four quadrants of the adjacency matrix with probabilities **a**, **b**,
+/// **c** and **d**, which default to the Graph500 values 0.57, 0.19, 0.19 and
+/// 0.05.
+///
+/// Noise perturbs the probabilities independently on every level, which
+/// smooths the degree distribution. A vertex permutation relabels the nodes
+/// randomly, as the Graph500 reference does. Edges are streamed and may
+/// contain loops and duplicates.
+///
+pub struct Rmat {
+    scale: u32,
+    edge_factor: usize,
+    probabilities: [f64; 4],
+    noise: f64,
+    permute: bool,
+    undirected: bool,
+    seed: u64,
+}
+
+impl Rmat {
+    pub fn new(scale: u32, edge_factor: usize) -> Rmat {
+        assert!(scale < usize::BITS, "scale too large");
+        assert!(edge_factor.checked_mul(1 << scale).is_some(), "too many edges");
+        Rmat { scale, edge_factor, probabilities: [0.57, 0.19, 0.19, 0.05], noise: 0.0, permute: false, undirected: false, seed: rng::random_seed() }
+    }
+
+    ///
+    /// Sets the quadrant probabilities, which must sum to one.
+    ///
+    pub fn with_probabilities(mut self, a: f64, b: f64, c: f64, d: f64) -> Rmat {
+        assert!(a >= 0.0 && b >= 0.0 && c >= 0.0 && d >= 0.0, "probabilities must not be negative");
+        assert!((a + b + c + d - 1.0).abs() < 1e-9, "probabilities must sum to one");
+        self.probabilities = [a, b, c, d];
+        self.check_noise()
+    }
+
+    ///
+    /// Shifts probability mass between the quadrants on every level by a
+    /// uniform amount in `[-noise, noise]`, keeping the sum at one.
+    ///
+    pub fn with_noise(mut self, noise: f64) -> Rmat {
+        self.noise = noise;
+        self.check_noise()
+    }
+
+    pub fn with_permutation(mut self) -> Rmat {
+        self.permute = true;
+        self
+    }
+
+    pub fn undirected(mut self) -> Rmat {
+        self.undirected = true;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Rmat {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn check_noise(self) -> Rmat {
+        let [a, b, c, d] = self.probabilities;
+        assert!(self.noise >= 0.0, "noise must not be negative");
+        assert!(self.noise <= b.min(c).min((a + d) / 2.0), "noise too large for the probabilities");
+        self
+    }
+}
+
+impl Generator for Rmat {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        Box::new(self.edge_chunks().unwrap().into_iter().flatten())
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(1 << self.scale)
+    }
+
+    fn edge_chunks<'a>(&'a self) -> Option<Vec<Box<dyn Iterator<Item=Edge> + Send + 'a>>> {
+        let mut rng = rng::seeded(self.seed);
+        let n = 1 << self.scale;
+
+        let permutation = if self.permute { Some(permutation(&mut rng, n)) } else { None };
+
+        let [a, b, c, d] = self.probabilities;
+        let levels = (0..self.scale).map(|_| {
+            let mu = if self.noise > 0.0 { rng.gen_range(-self.noise, self.noise) } else { 0.0 };
+            let shift = if mu != 0.0 { 2.0 * mu / (a + d) } else { 0.0 };
+            cumulative(&[a - shift * a, b + mu, c + mu, d - shift * d])
+        }).collect();
+
+        Some(stream(self.seed, levels, 2, self.edge_factor * n, permutation, self.undirected))
+    }
+}
+
+///
+/// Constructs a [stochastic Kronecker
+/// graph](https://jmlr.org/papers/v11/leskovec10a.html) from a `k x k`
+/// **initiator** matrix of probabilities raised to the Kronecker power
+/// **levels**, on `k^levels` nodes.
+///
+/// Edges are placed by recursively descending into a cell chosen in
+/// proportion to the initiator entries. The number of edges defaults to the
+/// expected count `(sum of entries)^levels`. Edges are streamed and may
+/// contain loops and duplicates.
+///
+pub struct Kronecker {
+    k: usize,
+    initiator: Vec<f64>,
+    levels: u32,
+    m: usize,
+    permute: bool,
+    undirected: bool,
+    seed: u64,
+}
+
+impl Kronecker {
+    pub fn new(initiator: Vec<Vec<f64>>, levels: u32) -> Kronecker {
+        let k = initiator.len();
+        assert!(k >= 2, "initiator must be at least 2 x 2");
+        assert!(initiator.iter().all(|row| row.len() == k), "initiator must be square");
+        assert!((k as f64).powi(levels as i32) < usize::MAX as f64, "too many levels");
+
+        let initiator = initiator.into_iter().flat_map(|row| row.into_iter()).collect::<Vec<f64>>();
+        assert!(initiator.iter().all(|&p| p >= 0.0), "initiator entries must not be negative");
+
+        let sum = initiator.iter().sum::<f64>();
+        assert!(sum > 0.0, "initiator must have a positive entry");
+
+        let m = sum.powi(levels as i32).round() as usize;
+        Kronecker { k, initiator, levels, m, permute: false, undirected: false, seed: rng::random_seed() }
+    }
+
+    pub fn with_edges(mut self, m: usize) -> Kronecker {
+        self.m = m;
+        self
+    }
+
+    pub fn with_permutation(mut self) -> Kronecker {
+        self.permute = true;
+        self
+    }
+
+    pub fn undirected(mut self) -> Kronecker {
+        self.undirected = true;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Kronecker {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn num_edges(&self) -> usize {
+        self.m
+    }
+}
+
+impl Generator for Kronecker {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        Box::new(self.edge_chunks().unwrap().into_iter().flatten())
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.k.pow(self.levels))
+    }
+
+    fn edge_chunks<'a>(&'a self) -> Option<Vec<Box<dyn Iterator<Item=Edge> + Send + 'a>>> {
+        let mut rng = rng::seeded(self.seed);
+        let n = self.k.pow(self.levels);
+
+        let permutation = if self.permute { Some(permutation(&mut rng, n)) } else { None };
+        let levels = vec![cumulative(&self.initiator); self.levels as usize];
+
+        Some(stream(self.seed, levels, self.k, self.m, permutation, self.undirected))
+    }
+}
+
+fn cumulative(weights: &[f64]) -> Vec<f64> {
+    let total = weights.iter().sum::<f64>();
+    weights.iter().scan(0.0, |acc, &w| {
+        *acc += w / total;
+        Some(*acc)
+    }).collect()
+}
+
+fn permutation(rng: &mut GeneratorRng, n: usize) -> Vec<Node> {
+    let mut permutation = (0..n).collect::<Vec<Node>>();
+    rng.shuffle(&mut permutation);
+    permutation
+}
+
+///
+/// Draws **m** independent edges, descending one level of the `k x k` cell
+/// distributions in **levels** per digit of the node ids. The draws are split
+/// into chunks of `CHUNK_EDGES`, each seeded on its own.
+///
+fn stream<'a>(seed: u64, levels: Vec<Vec<f64>>, k: usize, m: usize, permutation: Option<Vec<Node>>, undirected: bool) -> Vec<Box<dyn Iterator<Item=Edge> + Send + 'a>> {
+    let levels = Arc::new(levels);
+    let permutation = permutation.map(Arc::new);
+    let size = rng::CHUNK_EDGES as usize;
+
+    (0..m.div_ceil(size).max(1)).map(|chunk| {
+        let mut rng = rng::seeded_chunk(seed, chunk);
+        let levels = levels.clone();
+        let permutation = permutation.clone();
+
+        let pairs = (chunk * size..m.min((chunk + 1) * size)).map(move |_| {
+            let (mut u, mut v) = (0, 0);
+
+            for cells in levels.iter() {
+                let r = rng.gen::<f64>();
+                let cell = cells.iter().position(|&c| r < c).unwrap_or(cells.len() - 1);
+
+                u = u * k + cell / k;
+                v = v * k + cell % k;
+            }
+
+            match permutation {
+                Some(ref p) => (p[u], p[v]),
+                None => (u, v),
+            }
+        });
+
+        expand(pairs, undirected)
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use Generator;
+    use StaticGraph;
+    use Edge;
+
+    use representations::AdjacencyList;
+    use generators::Rmat;
+    use generators::Kronecker;
+
+    #[test]
+    fn rmat_counts() {
+        let rmat = Rmat::new(10, 16).with_seed(1);
+        let edges = rmat.edges().collect::<Vec<Edge>>();
+
+        assert_eq!(edges.len(), 16 * 1024);
+        assert!(edges.iter().all(|e| e.u() < 1024 && e.v() < 1024));
+        assert_eq!(rmat.num_nodes_hint(), Some(1024));
+    }
+
+    #[test]
+    fn rmat_skew() {
+        let graph = AdjacencyList::from_generator(&Rmat::new(12, 16).with_seed(2));
+
+        // Node 0 sits in the a-quadrant on every level
+        assert!(graph.neighbors(0).count() > 20 * 16);
+    }
+
+    #[test]
+    fn rmat_degenerate() {
+        let edges = Rmat::new(8, 2).with_probabilities(0.0, 1.0, 0.0, 0.0).with_seed(3).edges().collect::<Vec<Edge>>();
+        assert!(edges.iter().all(|&e| e == Edge::new(0, 255)));
+
+        let edges = Rmat::new(8, 2).with_probabilities(1.0, 0.0, 0.0, 0.0).with_permutation().with_seed(3).edges().collect::<Vec<Edge>>();
+        assert!(edges.iter().all(|&e| e == edges[0] && e.u() == e.v()));
+    }
+
+    #[test]
+    fn rmat_noise() {
+        let rmat = Rmat::new(10, 8).with_noise(0.1).with_permutation().undirected().with_seed(4);
+        let graph = AdjacencyList::from_generator(&rmat);
+
+        assert_eq!(graph.num_nodes(), 1024);
+        assert!(graph.edges().all(|e| graph.has_edge(e.v(), e.u())));
+    }
+
+    #[test]
+    #[should_panic]
+    fn rmat_noise_too_large() {
+        Rmat::new(10, 8).with_noise(0.3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rmat_too_many_edges() {
+        Rmat::new(usize::BITS - 1, 4);
+    }
+
+    #[test]
+    fn rmat_reproducible() {
+        let a = Rmat::new(8, 4).with_noise(0.05).with_permutation().with_seed(5);
+        let b = Rmat::new(8, 4).with_noise(0.05).with_permutation().with_seed(5);
+
+        assert_eq!(a.edges().collect::<Vec<Edge>>(), a.edges().collect::<Vec<Edge>>());
+        assert_eq!(a.edges().collect::<Vec<Edge>>(), b.edges().collect::<Vec<Edge>>());
+    }
+
+    #[test]
+    fn kronecker_expected_edges() {
+        let kronecker = Kronecker::new(vec![vec![0.9, 0.5], vec![0.5, 0.1]], 10).with_seed(6);
+
+        assert_eq!(kronecker.num_edges(), 1024);
+        assert_eq!(kronecker.edges().count(), 1024);
+        assert!(kronecker.edges().all(|e| e.u() < 1024 && e.v() < 1024));
+    }
+
+    #[test]
+    fn kronecker_three_by_three() {
+        let kronecker = Kronecker::new(vec![vec![0.9, 0.6, 0.1], vec![0.6, 0.4, 0.2], vec![0.1, 0.2, 0.7]], 6).with_edges(5000).with_seed(7);
+        let edges = kronecker.edges().collect::<Vec<Edge>>();
+
+        assert_eq!(kronecker.num_nodes_hint(), Some(729));
+        assert_eq!(edges.len(), 5000);
+        assert!(edges.iter().all(|e| e.u() < 729 && e.v() < 729));
+    }
+
+    #[test]
+    fn kronecker_zero_cells() {
+        let kronecker = Kronecker::new(vec![vec![1.0, 1.0], vec![1.0, 0.0]], 8).with_edges(1000).with_seed(8);
+
+        // Both endpoints never take the last row and column on the same level
+        assert!(kronecker.edges().all(|e| e.u() & e.v() == 0));
+    }
+
+    #[test]
+    fn chunks() {
+        let rmat = Rmat::new(10, 100).with_permutation().with_noise(0.05).with_seed(10);
+        let kronecker = Kronecker::new(vec![vec![0.9, 0.5, 0.2], vec![0.5, 0.3, 0.1], vec![0.2, 0.1, 0.6]], 9).with_seed(10);
+        let edges = rmat.edges().collect::<Vec<Edge>>();
+
+        assert_eq!(edges.len(), 100 * 1024);
+        assert_eq!(rmat.edge_chunks().unwrap().len(), 2);
+        for &threads in &[1, 3] {
+            assert_eq!(rmat.edges_parallel(threads).concat(), edges);
+            assert_eq!(kronecker.edges_parallel(threads).concat(), kronecker.edges().collect::<Vec<Edge>>());
+        }
+    }
+
+}
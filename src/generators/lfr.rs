@@ -0,0 +1,456 @@
+extern crate rand;
+
+use Generator;
+use Node;
+use Edge;
+use Error;
+use Result;
+
+use self::rand::Rng;
+
+use super::rng;
+use super::rng::GeneratorRng;
+use super::util::expand;
+
+use std::collections::HashSet;
+use std::iter;
+
+///
+/// Constructs a [Lancichinetti–Fortunato–Radicchi
+/// benchmark](https://doi.org/10.1103/PhysRevE.78.046110) graph with **n**
+/// nodes and planted communities. Degrees follow a power law with mean
+/// **average_degree** and maximum **max_degree**, community sizes follow a
+/// power law too, and each node has a fraction **mixing** of its edges
+/// leaving its communities.
+///
+/// Exponents default to 2 for degrees and 1 for community sizes, and
+/// community sizes range from the minimum degree to one above the maximum
+/// degree. With overlap, some nodes belong to several communities and split
+/// their internal edges evenly among them.
+///
+/// Edges are wired by a configuration model within every community and one
+/// across communities, with loops, multi-edges and external edges inside a
+/// shared community rewired by edge swaps, or dropped when that fails. Each
+/// edge is generated in both directions, and `communities` returns the
+/// ground truth.
+///
+pub struct Lfr {
+    n: usize,
+    average_degree: f64,
+    max_degree: usize,
+    mixing: f64,
+    degree_exponent: f64,
+    community_exponent: f64,
+    community_sizes: Option<(usize, usize)>,
+    overlapping: usize,
+    memberships: usize,
+    seed: u64,
+}
+
+impl Lfr {
+    pub fn new(n: usize, average_degree: f64, max_degree: usize, mixing: f64) -> Lfr {
+        assert!(max_degree < n, "max degree must be smaller than n");
+        assert!(average_degree >= 1.0 && average_degree <= max_degree as f64, "average degree must be between 1 and the max degree");
+        assert!((0.0..=1.0).contains(&mixing), "mixing must lie in [0, 1]");
+
+        Lfr {
+            n,
+            average_degree,
+            max_degree,
+            mixing,
+            degree_exponent: 2.0,
+            community_exponent: 1.0,
+            community_sizes: None,
+            overlapping: 0,
+            memberships: 1,
+            seed: rng::random_seed(),
+        }
+    }
+
+    pub fn with_exponents(mut self, degree: f64, community: f64) -> Lfr {
+        assert!(degree >= 0.0 && community >= 0.0, "exponents must not be negative");
+        self.degree_exponent = degree;
+        self.community_exponent = community;
+        self
+    }
+
+    ///
+    /// Fails with `Infeasible` if the largest community can't hold the
+    /// internal degree of a node, or overlapping nodes can't find enough
+    /// communities.
+    ///
+    pub fn with_community_sizes(mut self, min: usize, max: usize) -> Result<Lfr> {
+        assert!(min > 0 && min <= max && max <= self.n, "community sizes must satisfy 0 < min <= max <= n");
+        self.community_sizes = Some((min, max));
+        self.check()
+    }
+
+    ///
+    /// Puts **nodes** random nodes in **memberships** communities each.
+    /// Fails like `with_community_sizes`.
+    ///
+    pub fn with_overlap(mut self, nodes: usize, memberships: usize) -> Result<Lfr> {
+        assert!(nodes <= self.n, "cannot have more overlapping nodes than nodes");
+        assert!(memberships >= 2 || nodes == 0, "overlapping nodes need at least two memberships");
+        self.overlapping = nodes;
+        self.memberships = memberships.max(1);
+        self.check()
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Lfr {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    ///
+    /// Nodes of every community, each sorted.
+    ///
+    pub fn communities(&self) -> Vec<Vec<Node>> {
+        let (_, communities) = self.partition(&mut rng::seeded(self.seed));
+        communities.into_iter().map(|members| {
+            let mut nodes = members.into_iter().map(|(v, _)| v).collect::<Vec<Node>>();
+            nodes.sort();
+            nodes
+        }).collect()
+    }
+
+    ///
+    /// Rejects community sizes below the largest internal degree, and fewer
+    /// communities than an overlapping node's memberships. Any drawn sizes
+    /// and degrees are then placeable.
+    ///
+    fn check(self) -> Result<Lfr> {
+        let (_, max_size) = self.size_range();
+        let internal = self.internal(self.max_degree);
+        let largest_share = if self.overlapping < self.n { internal } else { internal.div_ceil(self.memberships) };
+        let communities = self.num_memberships() / max_size;
+
+        if largest_share >= max_size || (self.overlapping > 0 && communities < self.memberships) {
+            return Err(Error::Infeasible);
+        }
+
+        Ok(self)
+    }
+
+    fn size_range(&self) -> (usize, usize) {
+        self.community_sizes.unwrap_or((self.min_degree().round() as usize, self.max_degree + 1))
+    }
+
+    ///
+    /// Internal degree of a node of **degree**, over all its communities.
+    ///
+    fn internal(&self, degree: usize) -> usize {
+        ((1.0 - self.mixing) * degree as f64).round() as usize
+    }
+
+    fn num_memberships(&self) -> usize {
+        self.n + self.overlapping * (self.memberships - 1)
+    }
+
+    ///
+    /// Lower degree bound giving the requested average degree.
+    ///
+    fn min_degree(&self) -> f64 {
+        let max = self.max_degree as f64;
+        let (mut low, mut high) = (1.0, max);
+        for _ in 0..100 {
+            let middle = (low + high) / 2.0;
+            if power_law_mean(middle, max, self.degree_exponent) < self.average_degree {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        low
+    }
+
+    ///
+    /// Draws degrees and assigns nodes to communities, returning the degrees
+    /// and the members of every community with their internal degree in it.
+    ///
+    fn partition(&self, rng: &mut GeneratorRng) -> (Vec<usize>, Vec<Vec<(Node, usize)>>) {
+        let n = self.n;
+        let min_degree = self.min_degree();
+        let max = self.max_degree as f64;
+
+        let mut degrees = (0..n).map(|_| (sample(rng, min_degree, max, self.degree_exponent).round() as usize).min(self.max_degree)).collect::<Vec<usize>>();
+        if !degrees.iter().sum::<usize>().is_multiple_of(2) {
+            let v = rng.gen_range(0, n);
+            degrees[v] = if degrees[v] < self.max_degree { degrees[v] + 1 } else { degrees[v] - 1 };
+        }
+
+        let mut nodes = (0..n).collect::<Vec<Node>>();
+        rng.shuffle(&mut nodes);
+        let mut homeless = vec![];
+        for (i, &v) in nodes.iter().enumerate() {
+            let memberships = if i < self.overlapping { self.memberships } else { 1 };
+            let internal = self.internal(degrees[v]);
+            for j in 0..memberships {
+                homeless.push((v, internal / memberships + if j < internal % memberships { 1 } else { 0 }));
+            }
+        }
+
+        // The drawn sizes may all fall short of the largest internal degree
+        // that check allowed, so shares are capped to the largest community
+        let (min_size, max_size) = self.size_range();
+        let sizes = community_sizes(rng, homeless.len(), min_size, max_size, self.community_exponent);
+        let largest = sizes.iter().cloned().max().unwrap_or(1);
+        for member in &mut homeless {
+            member.1 = member.1.min(largest - 1);
+        }
+
+        // Largest internal degrees first; a full community evicts a random
+        // member, who looks for another home. Past the step budget, nodes
+        // are placed without evictions and communities outgrow their size.
+        homeless.sort_by_key(|&(_, share)| share);
+        let mut communities = vec![vec![]; sizes.len()];
+        let mut placed = vec![vec![]; n];
+        let mut steps = 0;
+
+        while let Some((v, share)) = homeless.pop() {
+            steps += 1;
+
+            let mut candidates = (0..sizes.len()).filter(|&c| sizes[c] > share && !placed[v].contains(&c)).collect::<Vec<usize>>();
+            if candidates.is_empty() {
+                candidates = (0..sizes.len()).filter(|&c| !placed[v].contains(&c)).collect();
+            }
+            let c = candidates[rng.gen_range(0, candidates.len())];
+            communities[c].push((v, share.min(sizes[c] - 1)));
+            placed[v].push(c);
+
+            if communities[c].len() > sizes[c] && steps < 100 * (n + 10) {
+                let evicted = communities[c].swap_remove(rng.gen_range(0, sizes[c]));
+                placed[evicted.0].retain(|&d| d != c);
+                homeless.push(evicted);
+            }
+        }
+
+        (degrees, communities)
+    }
+}
+
+impl Generator for Lfr {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let mut rng = rng::seeded(self.seed);
+        let (degrees, mut communities) = self.partition(&mut rng);
+
+        let mut membership = vec![vec![]; self.n];
+        for (c, members) in communities.iter().enumerate() {
+            for &(v, _) in members {
+                membership[v].push(c);
+            }
+        }
+
+        // Odd internal degree sums give up one stub to the outside
+        for members in &mut communities {
+            if !members.iter().map(|&(_, share)| share).sum::<usize>().is_multiple_of(2) {
+                let i = members.iter().position(|&(_, share)| share > 0).unwrap();
+                members[i].1 -= 1;
+            }
+        }
+
+        let mut present = HashSet::new();
+        let mut pairs = vec![];
+        let mut external = degrees;
+
+        for members in &communities {
+            let mut stubs = vec![];
+            for &(v, share) in members {
+                external[v] -= share;
+                stubs.extend(iter::repeat_n(v, share));
+            }
+            pairs.extend(wire(&mut rng, stubs, &mut present, |_, _| true));
+        }
+
+        let stubs = external.iter().enumerate().flat_map(|(v, &k)| iter::repeat_n(v, k)).collect();
+        let separate = |u: Node, v: Node| membership[u].iter().all(|c| !membership[v].contains(c));
+        pairs.extend(wire(&mut rng, stubs, &mut present, separate));
+
+        expand(pairs.into_iter(), true)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+///
+/// Pairs up **stubs** at random into pairs that are not loops, not already
+/// **present** and **allowed**. Bad pairs are swapped with a random good one
+/// when that makes both good, and dropped otherwise.
+///
+fn wire<F: Fn(Node, Node) -> bool>(rng: &mut GeneratorRng, mut stubs: Vec<Node>, present: &mut HashSet<(Node, Node)>, allowed: F) -> Vec<(Node, Node)> {
+    let key = |u: Node, v: Node| (u.min(v), u.max(v));
+    rng.shuffle(&mut stubs);
+
+    let mut good = vec![];
+    let mut bad = vec![];
+    for pair in stubs.chunks(2).filter(|pair| pair.len() == 2) {
+        let (u, v) = (pair[0], pair[1]);
+        if u != v && allowed(u, v) && present.insert(key(u, v)) {
+            good.push((u, v));
+        } else {
+            bad.push((u, v));
+        }
+    }
+
+    for (u, v) in bad {
+        for _ in 0..50 {
+            if good.is_empty() {
+                break;
+            }
+
+            let i = rng.gen_range(0, good.len());
+            let (x, y) = if rng.gen() { good[i] } else { (good[i].1, good[i].0) };
+            let valid = |a: Node, b: Node| a != b && allowed(a, b) && !present.contains(&key(a, b));
+
+            if valid(u, x) && valid(v, y) && key(u, x) != key(v, y) {
+                present.remove(&key(x, y));
+                present.insert(key(u, x));
+                present.insert(key(v, y));
+                good[i] = (u, x);
+                good.push((v, y));
+                break;
+            }
+        }
+    }
+
+    good
+}
+
+///
+/// Draws community sizes in [**min**, **max**] until they add up to
+/// **total**, trimming the last one or spreading it over the others.
+///
+fn community_sizes(rng: &mut GeneratorRng, total: usize, min: usize, max: usize, exponent: f64) -> Vec<usize> {
+    let mut sizes = vec![];
+    let mut sum = 0;
+    while sum < total {
+        let size = (sample(rng, min as f64, max as f64, exponent).round() as usize).clamp(min, max);
+        sizes.push(size);
+        sum += size;
+    }
+
+    let last = sizes.len() - 1;
+    sizes[last] -= sum - total;
+    if sizes[last] < min && last > 0 {
+        let mut left = sizes.pop().unwrap();
+        while left > 0 {
+            let c = rng.gen_range(0, sizes.len());
+            if sizes[c] < max || sizes.iter().all(|&size| size >= max) {
+                sizes[c] += 1;
+                left -= 1;
+            }
+        }
+    }
+
+    sizes
+}
+
+///
+/// Draws from the continuous power law `x^-exponent` on [**low**, **high**].
+///
+fn sample(rng: &mut GeneratorRng, low: f64, high: f64, exponent: f64) -> f64 {
+    let p = 1.0 - exponent;
+    let u = rng.gen::<f64>();
+    if p.abs() < 1e-12 {
+        low * (high / low).powf(u)
+    } else {
+        (low.powf(p) + u * (high.powf(p) - low.powf(p))).powf(1.0 / p)
+    }
+}
+
+fn power_law_mean(low: f64, high: f64, exponent: f64) -> f64 {
+    let integral = |p: f64| if (p + 1.0).abs() < 1e-12 { (high / low).ln() } else { (high.powf(p + 1.0) - low.powf(p + 1.0)) / (p + 1.0) };
+    if high > low {
+        integral(1.0 - exponent) / integral(-exponent)
+    } else {
+        low
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use Generator;
+    use StaticGraph;
+    use Error;
+
+    use representations::AdjacencyList;
+    use generators::Lfr;
+    use conformance::is_simple;
+    use conformance::is_symmetric;
+
+    fn memberships(n: usize, communities: &[Vec<usize>]) -> Vec<Vec<usize>> {
+        let mut memberships = vec![vec![]; n];
+        for (c, members) in communities.iter().enumerate() {
+            for &v in members {
+                memberships[v].push(c);
+            }
+        }
+        memberships
+    }
+
+    #[test]
+    fn benchmark() {
+        for &mixing in &[0.1, 0.3, 0.6] {
+            let lfr = Lfr::new(2000, 15.0, 50, mixing).with_seed(1);
+            let graph = AdjacencyList::from_generator(&lfr);
+            let communities = lfr.communities();
+            let memberships = memberships(2000, &communities);
+
+            let external = graph.edges().filter(|e| memberships[e.u()][0] != memberships[e.v()][0]).count() as f64;
+            let average = graph.num_edges() as f64 / 2000.0;
+
+            assert!(is_simple(&graph));
+            assert!(is_symmetric(&graph));
+            assert!(memberships.iter().all(|m| m.len() == 1));
+            assert!((external / graph.num_edges() as f64 - mixing).abs() < 0.05);
+            assert!(average > 13.5 && average < 16.5);
+            assert!((0..2000).all(|v| graph.neighbors(v).count() <= 50));
+        }
+    }
+
+    #[test]
+    fn community_sizes() {
+        let lfr = Lfr::new(1000, 10.0, 40, 0.2).with_exponents(2.5, 1.5).with_community_sizes(50, 120).unwrap().with_seed(2);
+        let communities = lfr.communities();
+
+        assert_eq!(communities.iter().map(|c| c.len()).sum::<usize>(), 1000);
+        assert!(communities.iter().all(|c| c.len() >= 50 && c.len() <= 120));
+        assert!(communities.iter().all(|c| c.windows(2).all(|w| w[0] < w[1])));
+    }
+
+    #[test]
+    fn overlap() {
+        let lfr = Lfr::new(1000, 12.0, 40, 0.2).with_overlap(100, 3).unwrap().with_seed(3);
+        let graph = AdjacencyList::from_generator(&lfr);
+        let memberships = memberships(1000, &lfr.communities());
+
+        assert_eq!(memberships.iter().filter(|m| m.len() == 3).count(), 100);
+        assert_eq!(memberships.iter().filter(|m| m.len() == 1).count(), 900);
+        assert!(is_simple(&graph));
+        assert!(is_symmetric(&graph));
+
+        let shared = graph.edges().filter(|e| memberships[e.u()].iter().any(|c| memberships[e.v()].contains(c))).count() as f64;
+        assert!((shared / graph.num_edges() as f64 - 0.8).abs() < 0.05);
+    }
+
+    #[test]
+    fn reproducible() {
+        let lfr = Lfr::new(500, 8.0, 30, 0.3).with_seed(4);
+
+        assert_eq!(lfr.communities(), lfr.communities());
+        assert_eq!(lfr.edges().collect::<Vec<_>>(), lfr.edges().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn infeasible() {
+        assert_eq!(Lfr::new(500, 8.0, 30, 0.0).with_community_sizes(10, 20).err(), Some(Error::Infeasible));
+        assert_eq!(Lfr::new(100, 5.0, 20, 0.2).with_overlap(50, 10).unwrap().with_community_sizes(10, 60).err(), Some(Error::Infeasible));
+        assert!(Lfr::new(500, 8.0, 30, 0.5).with_community_sizes(16, 20).is_ok());
+    }
+}
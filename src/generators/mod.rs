@@ -1,6 +1,67 @@
+mod rng;
 mod erdos;
+mod barabasi_albert;
+mod watts_strogatz;
+mod kronecker;
+mod stochastic_block_model;
+mod degree_sequence;
+mod random_regular;
+mod chung_lu;
+mod trees;
+mod dag;
+mod geometric;
+mod hyperbolic;
+mod lfr;
+mod forest_fire;
+mod copying_model;
 mod util;
+mod combinators;
 
 pub use self::erdos::Erdos;
+pub use self::erdos::ErdosGnm;
+pub use self::barabasi_albert::BarabasiAlbert;
+pub use self::watts_strogatz::WattsStrogatz;
+pub use self::kronecker::Rmat;
+pub use self::kronecker::Kronecker;
+pub use self::stochastic_block_model::StochasticBlockModel;
+pub use self::degree_sequence::ConfigurationModel;
+pub use self::degree_sequence::HavelHakimi;
+pub use self::degree_sequence::KleitmanWang;
+pub use self::degree_sequence::is_graphical;
+pub use self::degree_sequence::is_digraphical;
+pub use self::random_regular::RandomRegular;
+pub use self::chung_lu::ChungLu;
+pub use self::trees::RandomTree;
+pub use self::trees::RandomForest;
+pub use self::dag::RandomDag;
+pub use self::dag::LayeredDag;
+pub use self::geometric::RandomGeometric;
+pub use self::geometric::NearestNeighbors;
+pub use self::hyperbolic::Hyperbolic;
+pub use self::lfr::Lfr;
+pub use self::forest_fire::ForestFire;
+pub use self::copying_model::CopyingModel;
 
 pub use self::util::CompleteGraph;
+pub use self::util::Path;
+pub use self::util::Cycle;
+pub use self::util::Star;
+pub use self::util::Wheel;
+pub use self::util::CompleteBipartite;
+pub use self::util::Grid;
+pub use self::util::Hypercube;
+pub use self::util::KaryTree;
+pub use self::util::Ladder;
+pub use self::util::NamedGraph;
+pub use self::util::SmallGraph;
+
+pub use self::combinators::GeneratorExt;
+pub use self::combinators::Relabel;
+pub use self::combinators::ShuffleNodes;
+pub use self::combinators::FilterEdges;
+pub use self::combinators::Symmetrize;
+pub use self::combinators::Chain;
+pub use self::combinators::Take;
+pub use self::combinators::OffsetNodes;
+pub use self::combinators::Dedup;
+pub use self::combinators::WithoutSelfLoops;
@@ -0,0 +1,145 @@
+extern crate rand;
+
+use Generator;
+use Node;
+use Edge;
+use Error;
+use Result;
+
+use self::rand::Rng;
+
+use super::rng;
+use super::rng::GeneratorRng;
+use super::util::expand;
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+
+///
+/// Constructs a random simple **d**-regular graph on **n** nodes by the
+/// pairing method of Steger and Wormald: stubs are paired at random, pairs
+/// that would form a loop or multi-edge are returned to the pool, and the
+/// attempt restarts if no suitable pair remains. The result is
+/// asymptotically uniform for `d = o(n^(1/3))`.
+///
+/// Each edge is generated in both directions.
+///
+pub struct RandomRegular {
+    n: usize,
+    d: usize,
+    seed: u64,
+}
+
+impl RandomRegular {
+    ///
+    /// Fails with `NotGraphical` unless `d < n` and `n * d` is even and fits
+    /// into `usize`.
+    ///
+    pub fn new(n: usize, d: usize) -> Result<RandomRegular> {
+        let stubs = n.checked_mul(d).ok_or(Error::NotGraphical)?;
+        if !stubs.is_multiple_of(2) || (d > 0 && d >= n) {
+            return Err(Error::NotGraphical);
+        }
+
+        Ok(RandomRegular { n, d, seed: rng::random_seed() })
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> RandomRegular {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn attempt(&self, rng: &mut GeneratorRng) -> Option<Vec<(Node, Node)>> {
+        let mut pairs = vec![];
+        let mut present = HashSet::new();
+        let mut stubs = (0..self.n).flat_map(|v| (0..self.d).map(move |_| v)).collect::<Vec<Node>>();
+
+        while !stubs.is_empty() {
+            let mut potential = BTreeMap::new();
+            rng.shuffle(&mut stubs);
+
+            for pair in stubs.chunks(2) {
+                let (u, v) = (pair[0].min(pair[1]), pair[0].max(pair[1]));
+                if u != v && present.insert((u, v)) {
+                    pairs.push((u, v));
+                } else {
+                    *potential.entry(u).or_insert(0) += 1;
+                    *potential.entry(v).or_insert(0) += 1;
+                }
+            }
+
+            let open = potential.keys().cloned().collect::<Vec<Node>>();
+            let suitable = open.iter().enumerate().any(|(i, &u)| open[i + 1..].iter().any(|&v| !present.contains(&(u, v))));
+            if !potential.is_empty() && !suitable {
+                return None;
+            }
+
+            stubs = potential.into_iter().flat_map(|(v, count)| (0..count).map(move |_| v)).collect();
+        }
+
+        Some(pairs)
+    }
+}
+
+impl Generator for RandomRegular {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let mut rng = rng::seeded(self.seed);
+
+        let pairs = loop {
+            if let Some(pairs) = self.attempt(&mut rng) {
+                break pairs;
+            }
+        };
+
+        expand(pairs.into_iter(), true)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use Generator;
+    use StaticGraph;
+    use Edge;
+    use Error;
+
+    use representations::AdjacencyList;
+    use generators::RandomRegular;
+    use conformance::is_simple;
+    use conformance::is_symmetric;
+
+    #[test]
+    fn regular() {
+        for &(n, d) in &[(10, 3), (100, 4), (7, 6), (50, 0), (1000, 5)] {
+            let graph = AdjacencyList::from_generator(&RandomRegular::new(n, d).unwrap().with_seed(n as u64));
+
+            assert_eq!(graph.num_nodes(), n);
+            assert!((0..n).all(|v| graph.neighbors(v).count() == d));
+            assert!(is_simple(&graph));
+            assert!(is_symmetric(&graph));
+        }
+    }
+
+    #[test]
+    fn regular_not_graphical() {
+        assert_eq!(RandomRegular::new(5, 3).err(), Some(Error::NotGraphical));
+        assert_eq!(RandomRegular::new(4, 4).err(), Some(Error::NotGraphical));
+        assert_eq!(RandomRegular::new(usize::MAX, 4).err(), Some(Error::NotGraphical));
+    }
+
+    #[test]
+    fn regular_reproducible() {
+        let a = RandomRegular::new(60, 3).unwrap().with_seed(1);
+        let b = RandomRegular::new(60, 3).unwrap().with_seed(1);
+
+        assert_eq!(a.edges().collect::<Vec<Edge>>(), a.edges().collect::<Vec<Edge>>());
+        assert_eq!(a.edges().collect::<Vec<Edge>>(), b.edges().collect::<Vec<Edge>>());
+    }
+}
@@ -0,0 +1,106 @@
+extern crate rand;
+
+use self::rand::Rng;
+use self::rand::SeedableRng;
+use self::rand::XorShiftRng;
+
+///
+/// Random number generator used by all random generators. It is created
+/// afresh from the generator's seed on every call to `edges()`, so the edge
+/// stream is the same each time.
+///
+pub(crate) type GeneratorRng = XorShiftRng;
+
+///
+/// Expands **seed** into the state of a `GeneratorRng` using SplitMix64,
+/// which never yields the all-zero state XorShift can't leave.
+///
+pub(crate) fn seeded(seed: u64) -> GeneratorRng {
+    let mut state = seed;
+    let mut words = [0u32; 4];
+
+    for pair in words.chunks_mut(2) {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+
+        pair[0] = z as u32;
+        pair[1] = (z >> 32) as u32;
+    }
+
+    if words.iter().all(|&w| w == 0) {
+        words[0] = 1;
+    }
+
+    XorShiftRng::from_seed(words)
+}
+
+///
+/// Expected number of edges per chunk of generators that split their edges
+/// into independently seeded chunks.
+///
+pub(crate) const CHUNK_EDGES: f64 = 65536.0;
+
+///
+/// Random number generator of chunk **chunk** of a generator with **seed**,
+/// independent of the other chunks' generators.
+///
+pub(crate) fn seeded_chunk(seed: u64, chunk: usize) -> GeneratorRng {
+    seeded(seed ^ (chunk as u64 + 1).wrapping_mul(0xd1b5_4a32_d192_ed03).rotate_left(29))
+}
+
+///
+/// Draws a seed from the thread-local RNG, for generators constructed
+/// without an explicit one.
+///
+pub(crate) fn random_seed() -> u64 {
+    rand::thread_rng().gen()
+}
+
+///
+/// Number of failed Bernoulli(**p**) trials before the next success, drawn
+/// from the geometric distribution. Infinite if **p** is zero.
+///
+pub(crate) fn skip(rng: &mut GeneratorRng, p: f64) -> f64 {
+    if p >= 1.0 {
+        0.0
+    } else if p <= 0.0 {
+        f64::INFINITY
+    } else {
+        ((1.0 - rng.gen::<f64>()).ln() / (1.0 - p).ln()).floor()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rand::Rng;
+    use super::seeded;
+    use super::seeded_chunk;
+
+    #[test]
+    fn same_seed_same_stream() {
+        let a = seeded(42).gen_iter::<u64>().take(10).collect::<Vec<u64>>();
+        let b = seeded(42).gen_iter::<u64>().take(10).collect::<Vec<u64>>();
+        let c = seeded(43).gen_iter::<u64>().take(10).collect::<Vec<u64>>();
+
+        assert_eq!(a, b);
+        assert!(a != c);
+    }
+
+    #[test]
+    fn chunk_streams() {
+        let a = seeded_chunk(42, 0).gen_iter::<u64>().take(10).collect::<Vec<u64>>();
+        let b = seeded_chunk(42, 1).gen_iter::<u64>().take(10).collect::<Vec<u64>>();
+        let c = seeded(42).gen_iter::<u64>().take(10).collect::<Vec<u64>>();
+
+        assert!(a != b && a != c && b != c);
+        assert_eq!(a, seeded_chunk(42, 0).gen_iter::<u64>().take(10).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn zero_seed() {
+        assert!(seeded(0).gen::<u64>() != 0);
+    }
+}
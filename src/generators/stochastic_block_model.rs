@@ -0,0 +1,417 @@
+extern crate rand;
+
+use Generator;
+use Node;
+use Edge;
+
+use self::rand::Rng;
+
+use super::rng;
+use super::rng::GeneratorRng;
+use super::util::expand;
+
+use std::ops::Range;
+
+///
+/// Constructs a [stochastic block
+/// model](https://en.wikipedia.org/wiki/Stochastic_block_model) graph. The
+/// nodes are split into consecutive blocks of the given **sizes**, and a pair
+/// of nodes in blocks `r` and `s` is connected with probability
+/// `probabilities[r][s]`.
+///
+/// Every block pair is sampled by geometric skipping in time proportional to
+/// its number of edges. Like `Erdos`, the model is directed with loops by
+/// default, and `undirected` takes each unordered pair once, which requires a
+/// symmetric probability matrix.
+///
+/// The degree-corrected variant keeps the number of edges of each block pair
+/// and picks their endpoints within the blocks in proportion to per-node
+/// weights, so it may generate multi-edges.
+///
+pub struct StochasticBlockModel {
+    offsets: Vec<Node>,
+    probabilities: Vec<Vec<f64>>,
+    weights: Option<Vec<Vec<f64>>>,
+    loops: bool,
+    undirected: bool,
+    seed: u64,
+}
+
+impl StochasticBlockModel {
+    pub fn new(sizes: Vec<usize>, probabilities: Vec<Vec<f64>>) -> StochasticBlockModel {
+        let k = sizes.len();
+        assert!(probabilities.len() == k && probabilities.iter().all(|row| row.len() == k), "probabilities must be a square matrix with one row per block");
+        assert!(probabilities.iter().flat_map(|row| row.iter()).all(|p| (0.0..=1.0).contains(p)), "probabilities must lie in [0, 1]");
+
+        let offsets = Some(0).into_iter().chain(sizes.iter().scan(0, |acc, &size| {
+            *acc += size;
+            Some(*acc)
+        })).collect();
+
+        StochasticBlockModel { offsets, probabilities, weights: None, loops: true, undirected: false, seed: rng::random_seed() }
+    }
+
+    ///
+    /// Planted partition model with **blocks** blocks of **size** nodes,
+    /// connected with probability **p_in** within and **p_out** between
+    /// blocks.
+    ///
+    pub fn planted_partition(blocks: usize, size: usize, p_in: f64, p_out: f64) -> StochasticBlockModel {
+        let probabilities = (0..blocks).map(|r| (0..blocks).map(|s| if r == s { p_in } else { p_out }).collect()).collect();
+        StochasticBlockModel::new(vec![size; blocks], probabilities)
+    }
+
+    ///
+    /// Degree correction with one non-negative **weights** entry per node.
+    /// Each block needs a positive total weight, and without loops, blocks
+    /// with edges inside need positive weight on at least two nodes.
+    ///
+    pub fn with_degree_correction(mut self, weights: Vec<f64>) -> StochasticBlockModel {
+        assert!(weights.len() == self.num_nodes(), "one weight per node required");
+        assert!(weights.iter().all(|&w| w >= 0.0), "weights must not be negative");
+
+        let cumulative = (0..self.num_blocks()).map(|b| {
+            weights[self.block_nodes(b)].iter().scan(0.0, |acc, &w| {
+                *acc += w;
+                Some(*acc)
+            }).collect::<Vec<f64>>()
+        }).collect::<Vec<_>>();
+
+        assert!(cumulative.iter().all(|c| c.last().is_none_or(|&total| total > 0.0)), "every block needs a positive total weight");
+        self.weights = Some(cumulative);
+        self.check_loopless_weights();
+        self
+    }
+
+    pub fn without_loops(mut self) -> StochasticBlockModel {
+        self.loops = false;
+        self.check_loopless_weights();
+        self
+    }
+
+    pub fn undirected(mut self) -> StochasticBlockModel {
+        let k = self.num_blocks();
+        assert!((0..k).all(|r| (0..k).all(|s| self.probabilities[r][s] == self.probabilities[s][r])), "undirected model needs symmetric probabilities");
+        self.undirected = true;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> StochasticBlockModel {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.offsets[self.offsets.len() - 1]
+    }
+
+    pub fn num_blocks(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    ///
+    /// Ground-truth block of **node**.
+    ///
+    pub fn block(&self, node: Node) -> Option<usize> {
+        if node < self.num_nodes() {
+            Some(self.offsets.partition_point(|&o| o <= node) - 1)
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// Ground-truth block of every node.
+    ///
+    pub fn blocks(&self) -> Vec<usize> {
+        (0..self.num_blocks()).flat_map(|b| self.block_nodes(b).map(move |_| b)).collect()
+    }
+
+    pub fn block_nodes(&self, block: usize) -> Range<Node> {
+        self.offsets[block]..self.offsets[block + 1]
+    }
+
+    fn size(&self, block: usize) -> u64 {
+        (self.offsets[block + 1] - self.offsets[block]) as u64
+    }
+
+    ///
+    /// Number of candidate node pairs between blocks **r** and **s**.
+    ///
+    fn total(&self, r: usize, s: usize) -> u64 {
+        let (a, b) = (self.size(r), self.size(s));
+        match (r == s, self.undirected, self.loops) {
+            (false, _, _) => a * b,
+            (true, false, true) => a * a,
+            (true, false, false) => a * a.saturating_sub(1),
+            (true, true, true) => a * (a + 1) / 2,
+            (true, true, false) => a * a.saturating_sub(1) / 2,
+        }
+    }
+
+    ///
+    /// The **c**th candidate pair between blocks **r** and **s** in row-major
+    /// order. Undirected diagonal blocks hold the pairs with `v <= u`.
+    ///
+    fn pair(&self, r: usize, s: usize, c: u64) -> (Node, Node) {
+        let (u, v) = if r != s {
+            (c / self.size(s), c % self.size(s))
+        } else if !self.undirected && self.loops {
+            (c / self.size(r), c % self.size(r))
+        } else if !self.undirected {
+            let (u, x) = (c / (self.size(r) - 1), c % (self.size(r) - 1));
+            (u, if x >= u { x + 1 } else { x })
+        } else if self.loops {
+            let u = triangle_row(c);
+            (u, c - u * (u + 1) / 2)
+        } else {
+            let u = triangle_row(c);
+            (u + 1, c - u * (u + 1) / 2)
+        };
+
+        (self.offsets[r] + u as usize, self.offsets[s] + v as usize)
+    }
+
+    ///
+    /// Without loops, a block whose weight sits on a single node can't draw
+    /// its inner edges.
+    ///
+    fn check_loopless_weights(&self) {
+        if let (false, Some(weights)) = (self.loops, self.weights.as_ref()) {
+            let spread = |c: &Vec<f64>| c.iter().zip(Some(&0.0).into_iter().chain(c.iter())).filter(|&(w, prev)| w > prev).count() >= 2;
+            assert!((0..self.num_blocks()).all(|b| self.probabilities[b][b] == 0.0 || self.total(b, b) == 0 || spread(&weights[b])), "blocks without loops need positive weight on two nodes");
+        }
+    }
+
+    fn weighted(&self, rng: &mut GeneratorRng, cumulative: &[f64], block: usize) -> Node {
+        let target = rng.gen::<f64>() * cumulative[cumulative.len() - 1];
+        let i = cumulative.partition_point(|&c| c <= target).min(cumulative.len() - 1);
+        self.offsets[block] + i
+    }
+}
+
+///
+/// Largest `u` with `u (u + 1) / 2 <= c`.
+///
+fn triangle_row(c: u64) -> u64 {
+    let mut u = (((8.0 * c as f64 + 1.0).sqrt() - 1.0) / 2.0) as u64;
+    while u * (u + 1) / 2 > c {
+        u -= 1;
+    }
+    while (u + 1) * (u + 2) / 2 <= c {
+        u += 1;
+    }
+    u
+}
+
+impl Generator for StochasticBlockModel {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let k = self.num_blocks();
+        let undirected = self.undirected;
+        let block_pairs = (0..k).flat_map(|r| (0..k).map(move |s| (r, s))).filter(|&(r, s)| !undirected || s <= r).collect();
+
+        let edges = BlockEdges { model: self, rng: rng::seeded(self.seed), block_pairs, b: 0, c: 0, remaining: None };
+        expand(edges, self.undirected)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.num_nodes())
+    }
+}
+
+///
+/// Walks the block pairs in order. Plain models skip geometrically through
+/// the candidate pairs, degree-corrected ones count the selected pairs the
+/// same way and then draw **remaining** weighted endpoints.
+///
+struct BlockEdges<'a> {
+    model: &'a StochasticBlockModel,
+    rng: GeneratorRng,
+    block_pairs: Vec<(usize, usize)>,
+    b: usize,
+    c: u64,
+    remaining: Option<u64>,
+}
+
+impl<'a> BlockEdges<'a> {
+    fn count(&mut self, r: usize, s: usize) -> u64 {
+        let total = self.model.total(r, s) as f64;
+        let p = self.model.probabilities[r][s];
+
+        let mut position = 0.0;
+        let mut count = 0;
+        loop {
+            position += rng::skip(&mut self.rng, p);
+            if position >= total {
+                return count;
+            }
+            position += 1.0;
+            count += 1;
+        }
+    }
+}
+
+impl<'a> Iterator for BlockEdges<'a> {
+    type Item = (Node, Node);
+
+    fn next(&mut self) -> Option<(Node, Node)> {
+        let model = self.model;
+
+        while self.b < self.block_pairs.len() {
+            let (r, s) = self.block_pairs[self.b];
+
+            if let Some(ref weights) = model.weights {
+                let remaining = match self.remaining {
+                    Some(remaining) => remaining,
+                    None => self.count(r, s),
+                };
+
+                if remaining > 0 {
+                    self.remaining = Some(remaining - 1);
+                    loop {
+                        let u = model.weighted(&mut self.rng, &weights[r], r);
+                        let v = model.weighted(&mut self.rng, &weights[s], s);
+                        if model.loops || u != v {
+                            return Some((u, v));
+                        }
+                    }
+                }
+            } else {
+                let total = model.total(r, s);
+                let skip = rng::skip(&mut self.rng, model.probabilities[r][s]);
+
+                if (self.c as f64 + skip) < total as f64 {
+                    let c = self.c + skip as u64;
+                    self.c = c + 1;
+                    return Some(model.pair(r, s, c));
+                }
+            }
+
+            self.b += 1;
+            self.c = 0;
+            self.remaining = None;
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use Generator;
+    use StaticGraph;
+    use Edge;
+
+    use representations::AdjacencyList;
+    use generators::StochasticBlockModel;
+
+    fn distinct(edges: &[Edge]) -> usize {
+        let mut edges = edges.to_vec();
+        edges.sort();
+        edges.dedup();
+        edges.len()
+    }
+
+    #[test]
+    fn ground_truth() {
+        let sbm = StochasticBlockModel::new(vec![3, 0, 2], vec![vec![0.5; 3]; 3]);
+
+        assert_eq!(sbm.num_nodes(), 5);
+        assert_eq!(sbm.num_blocks(), 3);
+        assert_eq!(sbm.blocks(), vec![0, 0, 0, 2, 2]);
+        assert_eq!(sbm.block(2), Some(0));
+        assert_eq!(sbm.block(3), Some(2));
+        assert_eq!(sbm.block(5), None);
+        assert_eq!(sbm.block_nodes(1), 3..3);
+    }
+
+    #[test]
+    fn complete_pairs() {
+        let sizes = vec![4, 1, 3];
+        let ones = vec![vec![1.0; 3]; 3];
+
+        let cases = vec![
+            (StochasticBlockModel::new(sizes.clone(), ones.clone()), 64),
+            (StochasticBlockModel::new(sizes.clone(), ones.clone()).without_loops(), 56),
+            (StochasticBlockModel::new(sizes.clone(), ones.clone()).undirected(), 64),
+            (StochasticBlockModel::new(sizes.clone(), ones.clone()).undirected().without_loops(), 56),
+        ];
+
+        for (sbm, count) in cases {
+            let edges = sbm.edges().collect::<Vec<Edge>>();
+
+            assert_eq!(edges.len(), count);
+            assert_eq!(distinct(&edges), count);
+            assert!(edges.iter().all(|e| e.u() < 8 && e.v() < 8));
+        }
+    }
+
+    #[test]
+    fn planted_partition() {
+        let sbm = StochasticBlockModel::planted_partition(4, 250, 0.1, 0.001).undirected().without_loops().with_seed(1);
+        let graph = AdjacencyList::from_generator(&sbm);
+
+        let inside = graph.edges().filter(|e| sbm.block(e.u()) == sbm.block(e.v())).count();
+        let between = graph.edges().count() - inside;
+
+        // 4 * 250 * 249 * 0.1 and 1000 * 750 * 0.001 directed edges expected
+        assert!(inside > 22_000 && inside < 27_800);
+        assert!(between > 500 && between < 1000);
+        assert!(graph.edges().all(|e| e.u() != e.v() && graph.has_edge(e.v(), e.u())));
+    }
+
+    #[test]
+    fn disconnected_blocks() {
+        let sbm = StochasticBlockModel::new(vec![50, 50], vec![vec![0.2, 0.0], vec![0.0, 0.3]]).with_seed(2);
+
+        assert!(sbm.edges().all(|e| sbm.block(e.u()) == sbm.block(e.v())));
+    }
+
+    #[test]
+    fn degree_corrected() {
+        let weights = (0..400).map(|v| if v % 200 < 10 { 10.0 } else { 1.0 }).collect();
+        let sbm = StochasticBlockModel::planted_partition(2, 200, 0.05, 0.01).with_degree_correction(weights).without_loops().with_seed(3);
+        let graph = AdjacencyList::from_generator(&sbm);
+
+        let heavy = (0..400).filter(|v| v % 200 < 10).map(|v| graph.neighbors(v).count()).sum::<usize>();
+        let light = (0..400).filter(|v| v % 200 >= 10).map(|v| graph.neighbors(v).count()).sum::<usize>();
+
+        // Heavy nodes hold 100 of 290 weight units per block
+        assert!(heavy > light / 2);
+        assert!(graph.edges().all(|e| e.u() != e.v()));
+        assert!(graph.num_edges() > 4400 && graph.num_edges() < 5200);
+    }
+
+    #[test]
+    fn reproducible() {
+        let a = StochasticBlockModel::planted_partition(3, 30, 0.3, 0.05).with_seed(4);
+        let b = StochasticBlockModel::planted_partition(3, 30, 0.3, 0.05).with_seed(4);
+
+        assert_eq!(a.edges().collect::<Vec<Edge>>(), a.edges().collect::<Vec<Edge>>());
+        assert_eq!(a.edges().collect::<Vec<Edge>>(), b.edges().collect::<Vec<Edge>>());
+    }
+
+    #[test]
+    #[should_panic]
+    fn loopless_single_weight() {
+        StochasticBlockModel::new(vec![3], vec![vec![1.0]]).with_degree_correction(vec![1.0, 0.0, 0.0]).without_loops();
+    }
+
+    #[test]
+    fn loopless_small_blocks() {
+        let sbm = StochasticBlockModel::new(vec![1, 0, 2], vec![vec![1.0; 3]; 3]).without_loops().with_degree_correction(vec![1.0, 1.0, 1.0]).with_seed(5);
+
+        assert_eq!(sbm.edges().count(), 6);
+        assert!(sbm.edges().all(|e| e.u() != e.v()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn asymmetric_undirected() {
+        StochasticBlockModel::new(vec![2, 2], vec![vec![0.5, 0.1], vec![0.2, 0.5]]).undirected();
+    }
+}
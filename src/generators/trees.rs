@@ -0,0 +1,296 @@
+extern crate rand;
+
+use Generator;
+use Node;
+use Edge;
+
+use self::rand::Rng;
+
+use super::rng;
+use super::util::expand;
+
+use std::collections::VecDeque;
+
+///
+/// Constructs a uniformly random labeled tree on **n** nodes by decoding a
+/// random [Prüfer sequence](https://en.wikipedia.org/wiki/Pr%C3%BCfer_sequence)
+/// in O(n). Edges point from parent to child with node 0 as the root, unless
+/// undirected.
+///
+pub struct RandomTree {
+    n: usize,
+    undirected: bool,
+    seed: u64,
+}
+
+impl RandomTree {
+    pub fn new(n: usize) -> RandomTree {
+        RandomTree { n, undirected: false, seed: rng::random_seed() }
+    }
+
+    pub fn undirected(mut self) -> RandomTree {
+        self.undirected = true;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> RandomTree {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    ///
+    /// The Prüfer sequence the tree is decoded from.
+    ///
+    pub fn prufer_sequence(&self) -> Vec<Node> {
+        let mut rng = rng::seeded(self.seed);
+        (0..self.n.saturating_sub(2)).map(|_| rng.gen_range(0, self.n)).collect()
+    }
+}
+
+impl Generator for RandomTree {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let n = self.n;
+        let mut adjacency = vec![vec![]; n];
+        for (u, v) in decode_prufer(n, &self.prufer_sequence()) {
+            adjacency[u].push(v);
+            adjacency[v].push(u);
+        }
+
+        let mut pairs = vec![];
+        let mut visited = vec![false; n];
+        let mut q = VecDeque::new();
+
+        if n > 0 {
+            visited[0] = true;
+            q.push_back(0);
+        }
+
+        while let Some(u) = q.pop_front() {
+            for &v in &adjacency[u] {
+                if !visited[v] {
+                    visited[v] = true;
+                    pairs.push((u, v));
+                    q.push_back(v);
+                }
+            }
+        }
+
+        expand(pairs.into_iter(), self.undirected)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+///
+/// Tree edges encoded by **sequence**, which has `n - 2` entries below **n**.
+///
+fn decode_prufer(n: usize, sequence: &[Node]) -> Vec<(Node, Node)> {
+    if n < 2 {
+        return vec![];
+    }
+
+    let mut degree = vec![1; n];
+    for &v in sequence {
+        degree[v] += 1;
+    }
+
+    let mut pairs = Vec::with_capacity(n - 1);
+    let mut ptr = degree.iter().position(|&d| d == 1).unwrap();
+    let mut leaf = ptr;
+
+    for &v in sequence {
+        pairs.push((leaf, v));
+        degree[leaf] = 0;
+        degree[v] -= 1;
+
+        if degree[v] == 1 && v < ptr {
+            leaf = v;
+        } else {
+            ptr += 1;
+            while degree[ptr] != 1 {
+                ptr += 1;
+            }
+            leaf = ptr;
+        }
+    }
+
+    pairs.push((leaf, n - 1));
+    pairs
+}
+
+///
+/// Constructs a uniformly random rooted forest on **n** nodes in which
+/// nodes `0..roots` are the roots of its trees, with Wilson's algorithm on
+/// the complete graph. A single root gives a uniformly random labeled tree.
+/// Edges point from parent to child unless undirected.
+///
+pub struct RandomForest {
+    n: usize,
+    roots: usize,
+    undirected: bool,
+    seed: u64,
+}
+
+impl RandomForest {
+    pub fn new(n: usize, roots: usize) -> RandomForest {
+        assert!(roots > 0 && roots <= n, "a forest needs between 1 and n roots");
+        RandomForest { n, roots, undirected: false, seed: rng::random_seed() }
+    }
+
+    pub fn undirected(mut self) -> RandomForest {
+        self.undirected = true;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> RandomForest {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Generator for RandomForest {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let mut rng = rng::seeded(self.seed);
+        let n = self.n;
+
+        let mut in_forest = (0..n).map(|v| v < self.roots).collect::<Vec<bool>>();
+        let mut next = vec![0; n];
+
+        // Loop-erased random walks until hitting the forest
+        for start in self.roots..n {
+            let mut u = start;
+            while !in_forest[u] {
+                let w = rng.gen_range(0, n - 1);
+                next[u] = if w >= u { w + 1 } else { w };
+                u = next[u];
+            }
+
+            let mut u = start;
+            while !in_forest[u] {
+                in_forest[u] = true;
+                u = next[u];
+            }
+        }
+
+        let pairs = (self.roots..n).map(|v| (next[v], v)).collect::<Vec<(Node, Node)>>();
+        expand(pairs.into_iter(), self.undirected)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use Generator;
+    use StaticGraph;
+    use Edge;
+
+    use representations::AdjacencyList;
+    use generators::RandomTree;
+    use generators::RandomForest;
+
+    use super::decode_prufer;
+
+    use std::collections::HashMap;
+
+    fn canonical(edges: &[Edge]) -> Vec<(usize, usize)> {
+        let mut pairs = edges.iter().map(|e| (e.u().min(e.v()), e.u().max(e.v()))).collect::<Vec<_>>();
+        pairs.sort();
+        pairs
+    }
+
+    #[test]
+    fn prufer_decoding() {
+        assert_eq!(decode_prufer(6, &[3, 3, 3, 4]), vec![(0, 3), (1, 3), (2, 3), (3, 4), (4, 5)]);
+        assert_eq!(decode_prufer(2, &[]), vec![(0, 1)]);
+        assert_eq!(decode_prufer(1, &[]), vec![]);
+    }
+
+    #[test]
+    fn tree() {
+        for &n in &[1, 2, 10, 1000] {
+            let graph = AdjacencyList::from_generator(&RandomTree::new(n).with_seed(n as u64));
+            let pred = graph.breadth_first_search(0);
+
+            assert_eq!(graph.num_edges(), n - 1);
+            assert!(pred.iter().all(|p| p.is_some()));
+            assert!(!graph.has_cycle());
+            assert!(graph.is_bipartite());
+        }
+    }
+
+    #[test]
+    fn tree_uniform() {
+        // Cayley: 16 labeled trees on 4 nodes, about 500 samples each
+        let mut counts = HashMap::new();
+        for seed in 0..8000 {
+            let edges = RandomTree::new(4).with_seed(seed).edges().collect::<Vec<Edge>>();
+            *counts.entry(canonical(&edges)).or_insert(0) += 1;
+        }
+
+        assert_eq!(counts.len(), 16);
+        assert!(counts.values().all(|&c| c > 400 && c < 600));
+    }
+
+    #[test]
+    fn tree_undirected() {
+        let tree = RandomTree::new(50).undirected().with_seed(1);
+        let graph = AdjacencyList::from_generator(&tree);
+
+        assert_eq!(graph.num_edges(), 98);
+        assert!(graph.edges().all(|e| graph.has_edge(e.v(), e.u())));
+        assert_eq!(tree.prufer_sequence().len(), 48);
+    }
+
+    #[test]
+    fn forest() {
+        let graph = AdjacencyList::from_generator(&RandomForest::new(500, 7).with_seed(2));
+        let mut reached = vec![0; 500];
+
+        for root in 0..7 {
+            for (v, p) in graph.breadth_first_search(root).iter().enumerate() {
+                if p.is_some() {
+                    reached[v] += 1;
+                }
+            }
+        }
+
+        assert_eq!(graph.num_edges(), 493);
+        assert!(reached.iter().all(|&r| r == 1));
+        assert!(graph.edges().all(|e| e.v() >= 7));
+    }
+
+    #[test]
+    fn forest_uniform() {
+        // 2 * 4^1 = 8 forests on 4 nodes rooted at 0 and 1
+        let mut counts = HashMap::new();
+        for seed in 0..4000 {
+            let edges = RandomForest::new(4, 2).with_seed(seed).edges().collect::<Vec<Edge>>();
+            *counts.entry(canonical(&edges)).or_insert(0) += 1;
+        }
+
+        assert_eq!(counts.len(), 8);
+        assert!(counts.values().all(|&c| c > 400 && c < 600));
+    }
+
+    #[test]
+    fn reproducible() {
+        let a = RandomTree::new(100).with_seed(3);
+        let b = RandomForest::new(100, 3).with_seed(3);
+
+        assert_eq!(a.edges().collect::<Vec<Edge>>(), a.edges().collect::<Vec<Edge>>());
+        assert_eq!(b.edges().collect::<Vec<Edge>>(), b.edges().collect::<Vec<Edge>>());
+    }
+}
@@ -1,4 +1,5 @@
 use Generator;
+use Node;
 use Edge;
 
 pub struct CompleteGraph {
@@ -12,11 +13,433 @@ impl CompleteGraph {
 }
 
 impl Generator for CompleteGraph {
-    fn edges<'a>(&'a self) -> Box<Iterator<Item=Edge> + 'a> {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
         Box::new((0..self.n).flat_map(move |u| (0..self.n).map(move |v| (u, v))).map(|(u, v)| Edge::new(u,v)))
     }
 }
 
+///
+/// Path `0 - 1 - ... - (n - 1)`, directed towards higher ids unless
+/// undirected.
+///
+pub struct Path {
+    n: usize,
+    undirected: bool,
+}
+
+impl Path {
+    pub fn new(n: usize) -> Path {
+        Path { n, undirected: false }
+    }
+
+    pub fn undirected(mut self) -> Path {
+        self.undirected = true;
+        self
+    }
+}
+
+impl Generator for Path {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        expand((1..self.n).map(|v| (v - 1, v)), self.undirected)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+///
+/// Cycle on **n** >= 3 nodes, directed `v -> v + 1` unless undirected.
+///
+pub struct Cycle {
+    n: usize,
+    undirected: bool,
+}
+
+impl Cycle {
+    pub fn new(n: usize) -> Cycle {
+        assert!(n >= 3, "a cycle needs at least 3 nodes");
+        Cycle { n, undirected: false }
+    }
+
+    pub fn undirected(mut self) -> Cycle {
+        self.undirected = true;
+        self
+    }
+}
+
+impl Generator for Cycle {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let n = self.n;
+        expand((0..n).map(move |v| (v, (v + 1) % n)), self.undirected)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+///
+/// Star on **n** nodes with center 0, directed towards the leaves unless
+/// undirected.
+///
+pub struct Star {
+    n: usize,
+    undirected: bool,
+}
+
+impl Star {
+    pub fn new(n: usize) -> Star {
+        Star { n, undirected: false }
+    }
+
+    pub fn undirected(mut self) -> Star {
+        self.undirected = true;
+        self
+    }
+}
+
+impl Generator for Star {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        expand((1..self.n).map(|v| (0, v)), self.undirected)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+///
+/// Wheel on **n** >= 4 nodes: hub 0 with spokes to the rim cycle
+/// `1 - 2 - ... - (n - 1)`. Directed from the hub and along the rim unless
+/// undirected.
+///
+pub struct Wheel {
+    n: usize,
+    undirected: bool,
+}
+
+impl Wheel {
+    pub fn new(n: usize) -> Wheel {
+        assert!(n >= 4, "a wheel needs at least 4 nodes");
+        Wheel { n, undirected: false }
+    }
+
+    pub fn undirected(mut self) -> Wheel {
+        self.undirected = true;
+        self
+    }
+}
+
+impl Generator for Wheel {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let n = self.n;
+        let spokes = (1..n).map(|v| (0, v));
+        let rim = (1..n).map(move |v| (v, v % (n - 1) + 1));
+
+        expand(spokes.chain(rim), self.undirected)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+///
+/// Complete bipartite graph K(m, n) between nodes `0..m` and `m..m + n`,
+/// directed from the first part unless undirected.
+///
+pub struct CompleteBipartite {
+    m: usize,
+    n: usize,
+    undirected: bool,
+}
+
+impl CompleteBipartite {
+    pub fn new(m: usize, n: usize) -> CompleteBipartite {
+        CompleteBipartite { m, n, undirected: false }
+    }
+
+    pub fn undirected(mut self) -> CompleteBipartite {
+        self.undirected = true;
+        self
+    }
+}
+
+impl Generator for CompleteBipartite {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let (m, n) = (self.m, self.n);
+        expand((0..m).flat_map(move |u| (m..m + n).map(move |v| (u, v))), self.undirected)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.m + self.n)
+    }
+}
+
+///
+/// Lattice with the side lengths **dims**, for example `&[rows, cols]` or
+/// `&[x, y, z]`. Nodes are numbered in row-major order, so in two dimensions
+/// node `r * cols + c` sits at row `r` and column `c`. Edges are directed
+/// towards higher coordinates unless undirected.
+///
+/// A torus also wraps every side longer than two around to its start.
+///
+pub struct Grid {
+    dims: Vec<usize>,
+    periodic: bool,
+    undirected: bool,
+}
+
+impl Grid {
+    pub fn new(dims: &[usize]) -> Grid {
+        Grid { dims: dims.to_vec(), periodic: false, undirected: false }
+    }
+
+    pub fn torus(dims: &[usize]) -> Grid {
+        Grid { dims: dims.to_vec(), periodic: true, undirected: false }
+    }
+
+    pub fn undirected(mut self) -> Grid {
+        self.undirected = true;
+        self
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.dims.iter().product()
+    }
+}
+
+impl Generator for Grid {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let mut strides = vec![1; self.dims.len()];
+        for d in (1..self.dims.len()).rev() {
+            strides[d - 1] = strides[d] * self.dims[d];
+        }
+
+        let pairs = (0..self.num_nodes()).flat_map(move |v| {
+            let strides = strides.clone();
+            (0..self.dims.len()).filter_map(move |d| {
+                let coord = (v / strides[d]) % self.dims[d];
+                if coord + 1 < self.dims[d] {
+                    Some((v, v + strides[d]))
+                } else if self.periodic && self.dims[d] > 2 {
+                    Some((v, v - coord * strides[d]))
+                } else {
+                    None
+                }
+            })
+        });
+
+        expand(pairs, self.undirected)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.num_nodes())
+    }
+}
+
+///
+/// Hypercube Q_d on `2^d` nodes, joining nodes whose ids differ in one bit.
+/// Directed towards the higher id unless undirected.
+///
+pub struct Hypercube {
+    d: u32,
+    undirected: bool,
+}
+
+impl Hypercube {
+    pub fn new(d: u32) -> Hypercube {
+        assert!(d < usize::BITS, "dimension too large");
+        Hypercube { d, undirected: false }
+    }
+
+    pub fn undirected(mut self) -> Hypercube {
+        self.undirected = true;
+        self
+    }
+}
+
+impl Generator for Hypercube {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let d = self.d;
+        let pairs = (0..1usize << d).flat_map(move |u| (0..d).map(move |b| 1 << b).filter(move |&bit| u & bit == 0).map(move |bit| (u, u | bit)));
+
+        expand(pairs, self.undirected)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(1 << self.d)
+    }
+}
+
+///
+/// Full **k**-ary tree of the given **height**, where height 0 is a single
+/// root. Node `v > 0` has parent `(v - 1) / k`. Edges point from parent to
+/// child unless undirected.
+///
+pub struct KaryTree {
+    k: usize,
+    n: usize,
+    undirected: bool,
+}
+
+impl KaryTree {
+    pub fn new(k: usize, height: u32) -> KaryTree {
+        assert!(k > 0, "k must be positive");
+        let n = (0..height + 1).map(|level| k.pow(level)).sum();
+        KaryTree { k, n, undirected: false }
+    }
+
+    pub fn undirected(mut self) -> KaryTree {
+        self.undirected = true;
+        self
+    }
+}
+
+impl Generator for KaryTree {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let k = self.k;
+        expand((1..self.n).map(move |v| ((v - 1) / k, v)), self.undirected)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+///
+/// Ladder with rails `0..n` and `n..2n` and rungs `(i, n + i)`. The
+/// circular ladder also closes both rails into cycles. Directed towards
+/// higher ids along rails and rungs, and back to the rail start for the
+/// closing edges, unless undirected.
+///
+pub struct Ladder {
+    n: usize,
+    circular: bool,
+    undirected: bool,
+}
+
+impl Ladder {
+    pub fn new(n: usize) -> Ladder {
+        Ladder { n, circular: false, undirected: false }
+    }
+
+    pub fn circular(n: usize) -> Ladder {
+        assert!(n >= 3, "a circular ladder needs at least 3 rungs");
+        Ladder { n, circular: true, undirected: false }
+    }
+
+    pub fn undirected(mut self) -> Ladder {
+        self.undirected = true;
+        self
+    }
+}
+
+impl Generator for Ladder {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let n = self.n;
+        let rails = (1..n).flat_map(move |i| vec![(i - 1, i), (n + i - 1, n + i)]);
+        let rungs = (0..n).map(move |i| (i, n + i));
+        let closing = if self.circular { vec![(n - 1, 0), (2 * n - 1, n)] } else { vec![] };
+
+        expand(rails.chain(rungs).chain(closing), self.undirected)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(2 * self.n)
+    }
+}
+
+///
+/// Named small graphs with well-known properties.
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SmallGraph {
+    /// 3-regular, 10 nodes, girth 5, not bipartite.
+    Petersen,
+    /// 3-regular, 14 nodes, girth 6, bipartite.
+    Heawood,
+    /// 3-regular, 20 nodes, girth 5, planar.
+    Dodecahedron,
+    /// 3-regular, 12 nodes, no non-trivial automorphism.
+    Frucht,
+    /// 4-regular, 6 nodes, K(2, 2, 2).
+    Octahedron,
+    /// K4 without the edge 0 - 3.
+    Diamond,
+    /// Triangle 0 - 1 - 2 with pendant nodes 3 at 1 and 4 at 2.
+    Bull,
+}
+
+///
+/// Generator for a `SmallGraph`, directed from the lower to the higher id
+/// unless undirected.
+///
+pub struct NamedGraph {
+    n: usize,
+    pairs: Vec<(Node, Node)>,
+    undirected: bool,
+}
+
+impl NamedGraph {
+    pub fn new(graph: SmallGraph) -> NamedGraph {
+        let (n, pairs) = match graph {
+            SmallGraph::Petersen => (10, (0..5).flat_map(|i| vec![(i, (i + 1) % 5), (i, i + 5), (5 + i, 5 + (i + 2) % 5)]).collect()),
+            SmallGraph::Heawood => (14, lcf(14, &[5, -5])),
+            SmallGraph::Dodecahedron => (20, lcf(20, &[10, 7, 4, -4, -7, 10, -4, 7, -7, 4])),
+            SmallGraph::Frucht => (12, lcf(12, &[-5, -2, -4, 2, 5, -2, 2, 5, -2, -5, 4, 2])),
+            SmallGraph::Octahedron => (6, (0..6).flat_map(|u| (u + 1..6).map(move |v| (u, v))).filter(|&(u, v)| v != u + 1 || u % 2 == 1).collect()),
+            SmallGraph::Diamond => (4, vec![(0, 1), (0, 2), (1, 2), (1, 3), (2, 3)]),
+            SmallGraph::Bull => (5, vec![(0, 1), (0, 2), (1, 2), (1, 3), (2, 4)]),
+        };
+
+        let pairs = pairs.into_iter().map(|(u, v): (Node, Node)| (u.min(v), u.max(v))).collect();
+        NamedGraph { n, pairs, undirected: false }
+    }
+
+    pub fn undirected(mut self) -> NamedGraph {
+        self.undirected = true;
+        self
+    }
+}
+
+impl Generator for NamedGraph {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        expand(self.pairs.iter().cloned(), self.undirected)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+///
+/// Cubic Hamiltonian graph from its LCF notation: the cycle on **n** nodes
+/// plus a chord from `i` to `i + shifts[i % len]`.
+///
+fn lcf(n: usize, shifts: &[isize]) -> Vec<(Node, Node)> {
+    let cycle = (0..n).map(|i| (i, (i + 1) % n));
+    let chords = (0..n).map(|i| (i, (i as isize + shifts[i % shifts.len()]).rem_euclid(n as isize) as usize)).filter(|&(i, j)| i < j);
+
+    cycle.chain(chords).collect()
+}
+
+///
+/// Turns node pairs into edges, generating each pair in both directions
+/// when **undirected**. Loops are generated once.
+///
+pub(super) fn expand<'a, I: Iterator<Item=(Node, Node)> + Send + 'a>(pairs: I, undirected: bool) -> Box<dyn Iterator<Item=Edge> + Send + 'a> {
+    if undirected {
+        Box::new(pairs.flat_map(|(u, v)| {
+            let reverse = if u != v { Some(Edge::new(v, u)) } else { None };
+            Some(Edge::new(u, v)).into_iter().chain(reverse)
+        }))
+    } else {
+        Box::new(pairs.map(|(u, v)| Edge::new(u, v)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use StaticGraph;
@@ -24,7 +447,20 @@ mod tests {
     use representations::EdgeList;
     use representations::AdjacencyList;
     use generators::CompleteGraph;
+    use generators::Path;
+    use generators::Cycle;
+    use generators::Star;
+    use generators::Wheel;
+    use generators::CompleteBipartite;
+    use generators::Grid;
+    use generators::Hypercube;
+    use generators::KaryTree;
+    use generators::Ladder;
+    use generators::NamedGraph;
+    use generators::SmallGraph;
     use algorithms::breadth_first_search;
+    use Generator;
+    use Node;
 
     #[test]
     fn creation_edgelist() {
@@ -65,4 +501,172 @@ mod tests {
         assert!(pred[0].is_some());
         assert_eq!(pred[0].unwrap(), 0);
     }
+
+    fn depths(pred: &[Option<Node>]) -> Vec<Option<usize>> {
+        (0..pred.len()).map(|mut v| {
+            let mut depth = 0;
+            while pred[v] != Some(v) {
+                v = pred[v]?;
+                depth += 1;
+            }
+            Some(depth)
+        }).collect()
+    }
+
+    fn degrees(graph: &AdjacencyList) -> Vec<usize> {
+        (0..graph.num_nodes()).map(|v| graph.neighbors(v).count()).collect()
+    }
+
+    #[test]
+    fn path() {
+        let graph = AdjacencyList::from_generator(&Path::new(6));
+
+        assert_eq!(graph.num_edges(), 5);
+        assert!(graph.is_bipartite());
+        assert!(!graph.has_cycle());
+        assert_eq!(depths(&graph.breadth_first_search(0)), (0..6).map(Some).collect::<Vec<_>>());
+        assert_eq!(Path::new(6).undirected().edges().count(), 10);
+        assert_eq!(Path::new(0).edges().count(), 0);
+    }
+
+    #[test]
+    fn cycle() {
+        let even = AdjacencyList::from_generator(&Cycle::new(6));
+        let odd = AdjacencyList::from_generator(&Cycle::new(7).undirected());
+
+        assert_eq!(even.num_edges(), 6);
+        assert!(even.is_bipartite());
+        assert!(even.has_cycle());
+        assert_eq!(odd.num_edges(), 14);
+        assert!(!odd.is_bipartite());
+        assert_eq!(depths(&odd.breadth_first_search(0)), vec![Some(0), Some(1), Some(2), Some(3), Some(3), Some(2), Some(1)]);
+    }
+
+    #[test]
+    fn star_and_wheel() {
+        let star = AdjacencyList::from_generator(&Star::new(8));
+        let wheel = AdjacencyList::from_generator(&Wheel::new(8).undirected());
+
+        assert_eq!(star.num_edges(), 7);
+        assert!(star.is_bipartite());
+        assert!(!star.has_cycle());
+        assert_eq!(depths(&star.breadth_first_search(0)), Some(Some(0)).into_iter().chain((1..8).map(|_| Some(1))).collect::<Vec<_>>());
+
+        assert_eq!(wheel.num_edges(), 28);
+        assert!(!wheel.is_bipartite());
+        assert_eq!(degrees(&wheel), vec![7, 3, 3, 3, 3, 3, 3, 3]);
+    }
+
+    #[test]
+    fn complete_bipartite() {
+        let graph = AdjacencyList::from_generator(&CompleteBipartite::new(3, 4).undirected());
+
+        assert_eq!(graph.num_edges(), 24);
+        assert!(graph.is_bipartite());
+        assert_eq!(degrees(&graph), vec![4, 4, 4, 3, 3, 3, 3]);
+        assert_eq!(depths(&graph.breadth_first_search(0)), vec![Some(0), Some(2), Some(2), Some(1), Some(1), Some(1), Some(1)]);
+    }
+
+    #[test]
+    fn grid() {
+        let grid = AdjacencyList::from_generator(&Grid::new(&[4, 5]).undirected());
+
+        assert_eq!(grid.num_edges(), 2 * (4 * 4 + 3 * 5));
+        assert!(grid.is_bipartite());
+        assert_eq!(depths(&grid.breadth_first_search(0)), (0..20).map(|v| Some(v / 5 + v % 5)).collect::<Vec<_>>());
+
+        let directed = AdjacencyList::from_generator(&Grid::new(&[3, 3]));
+        assert!(directed.has_cycle());
+        assert!(directed.has_edge(4, 5) && directed.has_edge(4, 7) && !directed.has_edge(5, 4));
+
+        let cube = Grid::new(&[2, 3, 4]);
+        assert_eq!(cube.num_nodes(), 24);
+        assert_eq!(cube.edges().count(), 3 * 4 + 2 * 2 * 4 + 2 * 3 * 3);
+    }
+
+    #[test]
+    fn torus() {
+        let even = AdjacencyList::from_generator(&Grid::torus(&[4, 6]).undirected());
+        let odd = AdjacencyList::from_generator(&Grid::torus(&[5, 5]).undirected());
+        let thin = AdjacencyList::from_generator(&Grid::torus(&[2, 4]).undirected());
+
+        assert!(degrees(&even).iter().all(|&d| d == 4));
+        assert_eq!(even.num_edges(), 2 * 2 * 24);
+        assert!(even.is_bipartite());
+        assert!(!odd.is_bipartite());
+        assert!(degrees(&thin).iter().all(|&d| d == 3));
+        assert_eq!(Grid::torus(&[3, 3, 3]).edges().count(), 81);
+    }
+
+    #[test]
+    fn hypercube() {
+        let graph = AdjacencyList::from_generator(&Hypercube::new(5).undirected());
+
+        assert_eq!(graph.num_nodes(), 32);
+        assert_eq!(graph.num_edges(), 2 * 5 * 16);
+        assert!(degrees(&graph).iter().all(|&d| d == 5));
+        assert!(graph.is_bipartite());
+        assert_eq!(depths(&graph.breadth_first_search(0)), (0..32usize).map(|v| Some(v.count_ones() as usize)).collect::<Vec<_>>());
+        assert_eq!(Hypercube::new(0).edges().count(), 0);
+    }
+
+    #[test]
+    fn kary_tree() {
+        let tree = AdjacencyList::from_generator(&KaryTree::new(3, 3));
+
+        assert_eq!(tree.num_nodes(), 40);
+        assert_eq!(tree.num_edges(), 39);
+        assert!(!tree.has_cycle());
+        assert!(tree.is_bipartite());
+        assert_eq!(depths(&tree.breadth_first_search(0))[39], Some(3));
+        assert_eq!(depths(&tree.breadth_first_search(0))[4], Some(2));
+        assert_eq!(KaryTree::new(1, 4).edges().count(), 4);
+    }
+
+    #[test]
+    fn ladder() {
+        let ladder = AdjacencyList::from_generator(&Ladder::new(5).undirected());
+        let even = AdjacencyList::from_generator(&Ladder::circular(6).undirected());
+        let odd = AdjacencyList::from_generator(&Ladder::circular(5).undirected());
+
+        assert_eq!(ladder.num_edges(), 2 * (3 * 5 - 2));
+        assert!(ladder.is_bipartite());
+        assert_eq!(even.num_edges(), 2 * 18);
+        assert!(degrees(&even).iter().all(|&d| d == 3));
+        assert!(even.is_bipartite());
+        assert!(!odd.is_bipartite());
+    }
+
+    #[test]
+    fn named() {
+        let cases = vec![
+            (SmallGraph::Petersen, 10, 3, false),
+            (SmallGraph::Heawood, 14, 3, true),
+            (SmallGraph::Dodecahedron, 20, 3, false),
+            (SmallGraph::Frucht, 12, 3, false),
+            (SmallGraph::Octahedron, 6, 4, false),
+        ];
+
+        for (kind, n, d, bipartite) in cases {
+            let graph = AdjacencyList::from_generator(&NamedGraph::new(kind).undirected());
+
+            assert_eq!(graph.num_nodes(), n);
+            assert_eq!(graph.num_edges(), n * d);
+            assert!(degrees(&graph).iter().all(|&degree| degree == d), "{:?}", kind);
+            assert_eq!(graph.is_bipartite(), bipartite, "{:?}", kind);
+        }
+
+        assert_eq!(NamedGraph::new(SmallGraph::Diamond).edges().count(), 5);
+        assert_eq!(degrees(&AdjacencyList::from_generator(&NamedGraph::new(SmallGraph::Bull).undirected())), vec![2, 3, 3, 1, 1]);
+    }
+
+    #[test]
+    fn petersen_distances() {
+        let graph = AdjacencyList::from_generator(&NamedGraph::new(SmallGraph::Petersen).undirected());
+
+        // Diameter 2: three neighbors and six nodes at distance two
+        let depths = depths(&graph.breadth_first_search(0));
+        assert_eq!(depths.iter().filter(|&&d| d == Some(1)).count(), 3);
+        assert_eq!(depths.iter().filter(|&&d| d == Some(2)).count(), 6);
+    }
 }
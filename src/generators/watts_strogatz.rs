@@ -0,0 +1,182 @@
+extern crate rand;
+
+use Generator;
+use Node;
+use Edge;
+
+use self::rand::Rng;
+
+use super::rng;
+use super::util::expand;
+
+use std::collections::HashSet;
+
+///
+/// Constructs a [Watts–Strogatz small-world
+/// graph](https://en.wikipedia.org/wiki/Watts%E2%80%93Strogatz_model) with
+/// **n** nodes. Each node starts connected to its **k** nearest neighbors on
+/// a ring, `k / 2` on each side, and each lattice edge is rewired to a
+/// uniformly chosen new endpoint with probability **beta**.
+///
+/// The Newman–Watts variant keeps all lattice edges and instead adds a
+/// shortcut for each of them with probability **beta**. Both variants avoid
+/// loops and multi-edges and generate every edge in both directions.
+///
+pub struct WattsStrogatz {
+    n: usize,
+    k: usize,
+    beta: f64,
+    shortcuts: bool,
+    seed: u64,
+}
+
+impl WattsStrogatz {
+    pub fn new(n: usize, k: usize, beta: f64) -> WattsStrogatz {
+        assert!(k.is_multiple_of(2), "k must be even");
+        assert!(k < n || k == 0, "k must be smaller than n");
+        assert!((0.0..=1.0).contains(&beta), "beta must be a probability");
+
+        WattsStrogatz { n, k, beta, shortcuts: false, seed: rng::random_seed() }
+    }
+
+    ///
+    /// Adds shortcuts instead of rewiring lattice edges (Newman–Watts).
+    ///
+    pub fn newman_watts(mut self) -> WattsStrogatz {
+        self.shortcuts = true;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> WattsStrogatz {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Generator for WattsStrogatz {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        let mut rng = rng::seeded(self.seed);
+        let n = self.n;
+
+        let mut pairs = (1..self.k / 2 + 1).flat_map(|j| (0..n).map(move |u| (u, (u + j) % n))).collect::<Vec<(Node, Node)>>();
+        let mut present = pairs.iter().map(|&(u, v)| key(u, v)).collect::<HashSet<(Node, Node)>>();
+        let mut degrees = vec![self.k; n];
+
+        for i in 0..pairs.len() {
+            if rng.gen::<f64>() >= self.beta {
+                continue;
+            }
+
+            let (u, v) = pairs[i];
+            if degrees[u] >= n - 1 {
+                continue;
+            }
+
+            let mut w = rng.gen_range(0, n);
+            while w == u || present.contains(&key(u, w)) {
+                w = rng.gen_range(0, n);
+            }
+
+            present.insert(key(u, w));
+            degrees[u] += 1;
+            degrees[w] += 1;
+
+            if self.shortcuts {
+                pairs.push((u, w));
+            } else {
+                present.remove(&key(u, v));
+                degrees[u] -= 1;
+                degrees[v] -= 1;
+                pairs[i] = (u, w);
+            }
+        }
+
+        expand(pairs.into_iter(), true)
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.n)
+    }
+}
+
+fn key(u: Node, v: Node) -> (Node, Node) {
+    (u.min(v), u.max(v))
+}
+
+#[cfg(test)]
+mod tests {
+    use Generator;
+    use StaticGraph;
+    use Edge;
+
+    use representations::AdjacencyList;
+    use generators::WattsStrogatz;
+    use conformance::is_simple;
+    use conformance::is_symmetric;
+
+    #[test]
+    fn lattice() {
+        let graph = AdjacencyList::from_generator(&WattsStrogatz::new(10, 4, 0.0));
+
+        assert_eq!(graph.num_edges(), 40);
+        assert!(graph.has_edge(0, 1) && graph.has_edge(0, 2) && graph.has_edge(0, 9) && graph.has_edge(0, 8));
+        assert!(!graph.has_edge(0, 3));
+        assert!(is_simple(&graph));
+        assert!(is_symmetric(&graph));
+    }
+
+    #[test]
+    fn rewiring() {
+        let ws = WattsStrogatz::new(1000, 6, 0.5).with_seed(1);
+        let graph = AdjacencyList::from_generator(&ws);
+        let long = graph.edges().filter(|e| {
+            let d = (e.u() as isize - e.v() as isize).unsigned_abs();
+            d.min(1000 - d) > 3
+        }).count();
+
+        assert_eq!(graph.num_edges(), 6000);
+        assert!(long > 2000);
+        assert!(long < 4000);
+        assert!(is_simple(&graph));
+        assert!(is_symmetric(&graph));
+    }
+
+    #[test]
+    fn full_rewiring_dense() {
+        let graph = AdjacencyList::from_generator(&WattsStrogatz::new(7, 4, 1.0).with_seed(2));
+
+        assert_eq!(graph.num_edges(), 28);
+        assert!(is_simple(&graph));
+        assert!(is_symmetric(&graph));
+    }
+
+    #[test]
+    fn newman_watts() {
+        let graph = AdjacencyList::from_generator(&WattsStrogatz::new(1000, 4, 0.2).newman_watts().with_seed(3));
+
+        assert!(graph.num_edges() > 4000 + 2 * 300);
+        assert!(graph.num_edges() < 4000 + 2 * 500);
+        assert!((0..1000).all(|u| graph.has_edge(u, (u + 1) % 1000) && graph.has_edge(u, (u + 2) % 1000)));
+        assert!(is_simple(&graph));
+        assert!(is_symmetric(&graph));
+    }
+
+    #[test]
+    fn reproducible() {
+        let a = WattsStrogatz::new(200, 4, 0.3).with_seed(4);
+        let b = WattsStrogatz::new(200, 4, 0.3).with_seed(4);
+
+        assert_eq!(a.edges().collect::<Vec<Edge>>(), a.edges().collect::<Vec<Edge>>());
+        assert_eq!(a.edges().collect::<Vec<Edge>>(), b.edges().collect::<Vec<Edge>>());
+    }
+
+    #[test]
+    #[should_panic]
+    fn odd_k() {
+        WattsStrogatz::new(10, 3, 0.1);
+    }
+}
@@ -1,10 +1,16 @@
+#[macro_use]
+mod conformance;
 mod traits;
+mod error;
 pub mod algorithms;
 pub mod representations;
 pub mod generators;
+pub mod formats;
 
 pub use traits::Graph;
 pub use traits::Generator;
 pub use traits::StaticGraph;
 pub use traits::Node;
 pub use traits::Edge;
+pub use error::Error;
+pub use error::Result;
@@ -14,9 +14,13 @@ pub struct AdjacencyList {
 }
 
 impl Generator for AdjacencyList {
-    fn edges<'a>(&'a self) -> Box<Iterator<Item=Edge> + 'a> {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
         Box::new(self.adj.iter().enumerate().flat_map(|(u, vec)| vec.iter().map(move |v| Edge::new(u, *v))))
     }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.num_nodes())
+    }
 }
 
 impl StaticGraph for AdjacencyList {
@@ -27,11 +31,20 @@ impl StaticGraph for AdjacencyList {
             al.add_edge(e.u(), e.v());
         }
 
+        if let Some(n) = gen.num_nodes_hint() {
+            while al.adj.len() < n {
+                al.adj.push(vec![]);
+            }
+            if n > 0 {
+                al.num_nodes = cmp::max(al.num_nodes, n - 1);
+            }
+        }
+
         al
     }
 
     fn num_nodes(&self) -> usize {
-        if self.adj.len() == 0 {
+        if self.adj.is_empty() {
             0
         } else {
             self.num_nodes + 1
@@ -61,11 +74,11 @@ impl StaticGraph for AdjacencyList {
         false
     }
 
-    fn neighbors<'a>(&'a self, vertex: Node) -> Box<Iterator<Item=Node> + 'a> {
+    fn neighbors<'a>(&'a self, vertex: Node) -> Box<dyn Iterator<Item=Node> + 'a> {
         if vertex >= self.adj.len() {
             Box::new(iter::empty())
         } else {
-            Box::new(self.adj[vertex].iter().map(|&v| v))
+            Box::new(self.adj[vertex].iter().cloned())
         }
     }
 
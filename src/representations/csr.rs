@@ -5,6 +5,11 @@ use Edge;
 
 use representations::EdgeList;
 
+use std::cmp;
+use std::iter;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
 #[derive(Clone, Debug)]
 pub struct Csr {
     col: Vec<Node>,
@@ -25,10 +30,10 @@ impl<'a> Iterator for CsrIterator<'a> {
         if self.idx >= self.csr.num_edges() {
             None
         } else {
-            let ret = Some(Edge::new(self.u, self.csr.col[self.idx])); 
-            if self.idx + 1 >= self.csr.row[self.u+1] {
+            while self.idx >= self.csr.row[self.u+1] {
                 self.u += 1;
             }
+            let ret = Some(Edge::new(self.u, self.csr.col[self.idx]));
             self.idx += 1;
 
             ret
@@ -37,33 +42,153 @@ impl<'a> Iterator for CsrIterator<'a> {
 }
 
 impl Generator for Csr {
-    fn edges<'a>(&'a self) -> Box<Iterator<Item=Edge> + 'a> {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
         Box::new(CsrIterator { csr: self, u: 0, idx: 0 })
     }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        Some(self.num_nodes())
+    }
+}
+
+impl Csr {
+    ///
+    /// Builds a `Csr` from its raw arrays. The neighbors of node **u** are
+    /// `col[row[u]..row[u + 1]]`, so **row** holds one offset per node plus a
+    /// final offset equal to `col.len()`.
+    ///
+    /// Panics if the arrays are inconsistent.
+    ///
+    pub fn from_raw_parts(row: Vec<usize>, col: Vec<Node>) -> Csr {
+        assert!(!row.is_empty() && row[0] == 0, "row offsets must start at 0");
+        assert!(row.windows(2).all(|w| w[0] <= w[1]), "row offsets must be non-decreasing");
+        assert_eq!(row[row.len() - 1], col.len(), "last row offset must equal the number of edges");
+        assert!(col.iter().all(|&v| v < row.len() - 1), "column index out of range");
+
+        let num_edges = col.len();
+        Csr { col, row, num_edges }
+    }
+
+    ///
+    /// Builds the same `Csr` as `from_generator` from the edge chunks of
+    /// **gen** on up to **threads** threads. Every thread generates a
+    /// contiguous run of chunks once, sorting its edges into one bucket per
+    /// thread by source node. Thread `t` then owns the rows `u` with
+    /// `u % threads == t`: it counts their degrees and, once the row offsets
+    /// are known, scatters its bucket of every run in order. Generators
+    /// without chunks are built sequentially.
+    ///
+    pub fn from_generator_parallel<T: Generator + ?Sized>(gen: &T, threads: usize) -> Csr {
+        let chunks = match gen.edge_chunks() {
+            Some(chunks) => chunks,
+            None => return Csr::from_generator(&gen),
+        };
+        let threads = threads.clamp(1, cmp::max(chunks.len(), 1));
+
+        let runs = thread::scope(|scope| {
+            let handles = split_chunks(chunks, threads).into_iter().map(|run| scope.spawn(move || {
+                let mut buckets = vec![vec![]; threads];
+                let mut max_node = 0;
+                for e in run.into_iter().flatten() {
+                    max_node = cmp::max(max_node, cmp::max(e.u(), e.v()) + 1);
+                    buckets[e.u() % threads].push(e);
+                }
+                (buckets, max_node)
+            })).collect::<Vec<_>>();
+
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect::<Vec<(Vec<Vec<Edge>>, usize)>>()
+        });
+
+        let max_node = runs.iter().map(|run| run.1).max().unwrap_or(0);
+        let num_nodes = cmp::max(max_node, gen.num_nodes_hint().unwrap_or(0));
+
+        // Row u of thread t is its (u / threads)th row
+        let owned = |t: usize| (num_nodes + threads - 1 - t) / threads;
+        let degrees = thread::scope(|scope| {
+            let handles = (0..threads).map(|t| {
+                let runs = &runs;
+                scope.spawn(move || {
+                    let mut deg = vec![0; owned(t)];
+                    for (buckets, _) in runs {
+                        for e in &buckets[t] {
+                            deg[e.u() / threads] += 1;
+                        }
+                    }
+                    deg
+                })
+            }).collect::<Vec<_>>();
+
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect::<Vec<Vec<usize>>>()
+        });
+
+        let mut row = vec![0; num_nodes + 1];
+        for u in 0..num_nodes {
+            row[u + 1] = row[u] + degrees[u % threads][u / threads];
+        }
+
+        let num_edges = row[num_nodes];
+        let col = iter::repeat_with(|| AtomicUsize::new(0)).take(num_edges).collect::<Vec<AtomicUsize>>();
+        thread::scope(|scope| {
+            for t in 0..threads {
+                let (runs, row, col) = (&runs, &row, &col);
+                scope.spawn(move || {
+                    let mut offset = (0..owned(t)).map(|i| row[i * threads + t]).collect::<Vec<usize>>();
+                    for (buckets, _) in runs {
+                        for e in &buckets[t] {
+                            col[offset[e.u() / threads]].store(e.v(), Ordering::Relaxed);
+                            offset[e.u() / threads] += 1;
+                        }
+                    }
+                });
+            }
+        });
+
+        let col = col.into_iter().map(AtomicUsize::into_inner).collect();
+        Csr { col, row, num_edges }
+    }
+
+    pub fn row(&self) -> &[usize] {
+        &self.row
+    }
+
+    pub fn col(&self) -> &[Node] {
+        &self.col
+    }
+}
+
+///
+/// Splits **chunks** into **parts** contiguous runs of nearly equal length.
+///
+fn split_chunks<'a>(mut chunks: Vec<Box<dyn Iterator<Item=Edge> + Send + 'a>>, parts: usize) -> Vec<Vec<Box<dyn Iterator<Item=Edge> + Send + 'a>>> {
+    let count = chunks.len();
+    let mut split = (0..parts).rev().map(|t| chunks.split_off(count * t / parts)).collect::<Vec<_>>();
+    split.reverse();
+    split
 }
 
 impl StaticGraph for Csr {
     fn from_generator<T: Generator>(gen: &T) -> Self {
         let el = EdgeList::from_generator(gen);
+        let num_nodes = cmp::max(el.num_nodes(), gen.num_nodes_hint().unwrap_or(0));
 
-        let mut deg = vec![0; el.num_nodes()];
+        let mut deg = vec![0; num_nodes];
 
         for e in el.edges() {
             deg[e.u()] += 1;
         }
 
         let mut col = vec![0; el.num_edges()];
-        let mut row = vec![0; el.num_nodes() + 1];
+        let mut row = vec![0; num_nodes + 1];
 
         let mut cumul = 0;
-        for i in 0..el.num_nodes() {
+        for i in 0..num_nodes {
             row[i] = cumul;
             cumul += deg[i];
         }
 
-        row[el.num_nodes()] = el.num_edges();
+        row[num_nodes] = el.num_edges();
 
-        let mut idx = vec![0; el.num_nodes()];
+        let mut idx = vec![0; num_nodes];
 
         for e in el.edges() {
             col[row[e.u()] + idx[e.u()]] = e.v();
@@ -74,7 +199,7 @@ impl StaticGraph for Csr {
     }
 
     fn num_nodes(&self) -> usize {
-        if self.row.len() == 0 {
+        if self.row.is_empty() {
             0
         } else {
             self.row.len() - 1
@@ -86,7 +211,7 @@ impl StaticGraph for Csr {
     }
 
     fn has_edge(&self, from: Node, to: Node) -> bool {
-        if self.row.len() <= from {
+        if from >= self.num_nodes() {
             return false;
         }
 
@@ -98,9 +223,13 @@ impl StaticGraph for Csr {
         false
     }
 
-    fn neighbors<'a>(&'a self, vertex: Node) -> Box<Iterator<Item=Node> + 'a> {
+    fn neighbors<'a>(&'a self, vertex: Node) -> Box<dyn Iterator<Item=Node> + 'a> {
+        if vertex >= self.num_nodes() {
+            return Box::new(iter::empty());
+        }
+
         let num_neighbors = self.row[vertex+1] - self.row[vertex];
-        Box::new(self.col.iter().skip(self.row[vertex]).take(num_neighbors).map(|&u| u))
+        Box::new(self.col.iter().skip(self.row[vertex]).take(num_neighbors).cloned())
     }
 
     fn clear(&mut self) {
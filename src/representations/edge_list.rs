@@ -12,8 +12,8 @@ pub struct EdgeList {
 }
 
 impl Generator for EdgeList {
-    fn edges<'a>(&'a self) -> Box<Iterator<Item=Edge> + 'a> {
-        Box::new(self.edges.iter().map(|&e| e))
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        Box::new(self.edges.iter().cloned())
     }
 }
 
@@ -35,7 +35,7 @@ impl StaticGraph for EdgeList {
             max_node = cmp::max(max_node, e.v());
         }
 
-        if self.edges.len() > 0 {
+        if !self.edges.is_empty() {
             max_node + 1
         } else {
             0
@@ -56,7 +56,7 @@ impl StaticGraph for EdgeList {
         false
     }
 
-    fn neighbors<'a>(&'a self, vertex: Node) -> Box<Iterator<Item=Node> + 'a> {
+    fn neighbors<'a>(&'a self, vertex: Node) -> Box<dyn Iterator<Item=Node> + 'a> {
         Box::new(self.edges.iter().filter(move |e| e.u() == vertex).map(|e| e.v()))
     }
 
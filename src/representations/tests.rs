@@ -9,307 +9,13 @@ use representations::EdgeList;
 use representations::Csr;
 
 use generators::Erdos;
+use generators::Rmat;
+use generators::Path;
 
 
-#[test]
-fn creation_edgelist() {
-    let mut graph = EdgeList::new();
-
-    graph.add_edge(0,1);
-
-    assert!(graph.has_edge(0, 1));
-    assert!(!graph.has_edge(1, 0));
-    assert_eq!(graph.num_edges(), 1);
-    assert_eq!(graph.num_nodes(), 2);
-}
-
-#[test]
-fn creation_adjacencylist() {
-    let mut graph = AdjacencyList::new();
-
-    graph.add_edge(0,1);
-
-    assert!(graph.has_edge(0, 1));
-    assert!(!graph.has_edge(1, 0));
-    assert_eq!(graph.num_edges(), 1);
-    assert_eq!(graph.num_nodes(), 2);
-}
-
-#[test]
-fn creation_csr() {
-    let mut graph = EdgeList::new();
-    graph.add_edge(0,1);
-
-    let graph = Csr::from_generator(&graph);
-
-    assert!(graph.has_edge(0, 1));
-    assert!(!graph.has_edge(1, 0));
-    assert_eq!(graph.num_edges(), 1);
-    assert_eq!(graph.num_nodes(), 2);
-}
-
-#[test]
-fn add_edges_edgelist() {
-    let mut graph = EdgeList::new();
-
-    graph.add_edge(0,1);
-    graph.add_edge(1,0);
-    graph.add_edge(2,5);
-    graph.add_edge(0,3);
-    graph.add_edge(5,4);
-
-    assert!(graph.has_edge(2,5));
-    assert!(!graph.has_edge(5,2));
-    assert!(!graph.has_edge(3,2));
-    assert_eq!(graph.num_edges(), 5);
-    assert_eq!(graph.num_nodes(), 6);
-}
-
-#[test]
-fn add_edges_adjacencylist() {
-    let mut graph = AdjacencyList::new();
-
-    graph.add_edge(0,1);
-    graph.add_edge(1,0);
-    graph.add_edge(2,5);
-    graph.add_edge(0,3);
-    graph.add_edge(5,4);
-
-    assert!(graph.has_edge(2,5));
-    assert!(!graph.has_edge(5,2));
-    assert!(!graph.has_edge(3,2));
-    assert_eq!(graph.num_edges(), 5);
-    assert_eq!(graph.num_nodes(), 6);
-}
-
-#[test]
-fn add_edges_csr() {
-    let mut graph = EdgeList::new();
-
-    graph.add_edge(0,1);
-    graph.add_edge(1,0);
-    graph.add_edge(2,5);
-    graph.add_edge(0,3);
-    graph.add_edge(5,4);
-
-    let graph = Csr::from_generator(&graph);
-
-    assert!(graph.has_edge(2,5));
-    assert!(!graph.has_edge(5,2));
-    assert!(!graph.has_edge(3,2));
-    assert_eq!(graph.num_edges(), 5);
-    assert_eq!(graph.num_nodes(), 6);
-}
-
-
-#[test]
-fn add_many_edges_edgelist() {
-    let mut graph = EdgeList::new();
-
-    for u in 0..1000 {
-        for v in 0..12 {
-            graph.add_edge(u,v);
-        }
-    }
-
-    assert!(graph.has_edge(994,7));
-    assert!(!graph.has_edge(994,13));
-    assert_eq!(graph.num_edges(), 12000);
-    assert_eq!(graph.num_nodes(), 1000);
-}
-
-#[test]
-fn add_many_edges_adjacencylist() {
-    let mut graph = AdjacencyList::new();
-
-    for u in 0..1000 {
-        for v in 0..12 {
-            graph.add_edge(u,v);
-        }
-    }
-
-    assert!(graph.has_edge(994,7));
-    assert!(!graph.has_edge(994,13));
-    assert_eq!(graph.num_edges(), 12000);
-    assert_eq!(graph.num_nodes(), 1000);
-}
-
-#[test]
-fn add_many_edges_csr() {
-    let mut graph = EdgeList::new();
-
-    for u in 0..1000 {
-        for v in 0..12 {
-            graph.add_edge(u,v);
-        }
-    }
-
-    let graph = Csr::from_generator(&graph);
-
-    assert!(graph.has_edge(994,7));
-    assert!(!graph.has_edge(994,12));
-    assert_eq!(graph.num_edges(), 12000);
-    assert_eq!(graph.num_nodes(), 1000);
-}
-
-#[test]
-fn clear_graph_edgelist() {
-    let mut graph = EdgeList::new();
-
-    for u in 0..1000 {
-        for v in 0..12 {
-            graph.add_edge(u,v);
-        }
-    }
-
-    assert!(graph.has_edge(994,7));
-    assert!(!graph.has_edge(994,13));
-    assert_eq!(graph.num_edges(), 12000);
-    assert_eq!(graph.num_nodes(), 1000);
-
-    graph.clear();
-
-    assert!(!graph.has_edge(994,7));
-    assert_eq!(graph.num_edges(), 0);
-    assert_eq!(graph.num_nodes(), 0);
-}
-
-#[test]
-fn clear_graph_adjacencylist() {
-    let mut graph = AdjacencyList::new();
-
-    for u in 0..1000 {
-        for v in 0..12 {
-            graph.add_edge(u,v);
-        }
-    }
-
-    assert!(graph.has_edge(994,7));
-    assert!(!graph.has_edge(994,13));
-    assert_eq!(graph.num_edges(), 12000);
-    assert_eq!(graph.num_nodes(), 1000);
-
-    graph.clear();
-
-    assert!(!graph.has_edge(994,7));
-    assert_eq!(graph.num_edges(), 0);
-    assert_eq!(graph.num_nodes(), 0);
-}
-
-#[test]
-fn clear_graph_csr() {
-    let mut graph = EdgeList::new();
-
-    for u in 0..1000 {
-        for v in 0..12 {
-            graph.add_edge(u,v);
-        }
-    }
-
-    let mut graph = Csr::from_generator(&graph);
-
-    assert!(graph.has_edge(994,7));
-    assert!(!graph.has_edge(994,12));
-    assert_eq!(graph.num_edges(), 12000);
-    assert_eq!(graph.num_nodes(), 1000);
-
-    graph.clear();
-
-    assert!(!graph.has_edge(994,7));
-    assert_eq!(graph.num_edges(), 0);
-    assert_eq!(graph.num_nodes(), 0);
-}
-
-#[test]
-fn duplicate_edge_edgelist() {
-    let mut graph = EdgeList::new();
-
-    graph.add_edge(0,1);
-    graph.add_edge(0,1);
-    graph.add_edge(0,1);
-    graph.add_edge(0,1);
-
-    assert!(graph.has_edge(0, 1));
-    assert!(!graph.has_edge(1, 0));
-    assert_eq!(graph.num_edges(), 4);
-    assert_eq!(graph.num_nodes(), 2);
-}
-
-#[test]
-fn duplicate_edge_adjacencylist() {
-    let mut graph = AdjacencyList::new();
-
-    graph.add_edge(0,1);
-    graph.add_edge(0,1);
-    graph.add_edge(0,1);
-    graph.add_edge(0,1);
-
-    assert!(graph.has_edge(0, 1));
-    assert!(!graph.has_edge(1, 0));
-    assert_eq!(graph.num_edges(), 4);
-    assert_eq!(graph.num_nodes(), 2);
-}
-
-#[test]
-fn duplicate_edge_csr() {
-    let mut graph = EdgeList::new();
-
-    graph.add_edge(0,1);
-    graph.add_edge(0,1);
-    graph.add_edge(0,1);
-    graph.add_edge(0,1);
-
-    let graph = Csr::from_generator(&graph);
-
-    assert!(graph.has_edge(0, 1));
-    assert!(!graph.has_edge(1, 0));
-    assert_eq!(graph.num_edges(), 4);
-    assert_eq!(graph.num_nodes(), 2);
-}
-
-#[test]
-fn reverse_edge_edgelist() {
-    let mut graph = EdgeList::new();
-
-    graph.add_edge(0,1);
-    graph.add_edge(1,0);
-
-    assert!(graph.has_edge(0, 1));
-    assert!(graph.has_edge(1, 0));
-    assert!(!graph.has_edge(0, 0));
-    assert!(!graph.has_edge(1, 1));
-    assert_eq!(graph.num_edges(), 2);
-}
-
-#[test]
-fn reverse_edge_adjacencylist() {
-    let mut graph = AdjacencyList::new();
-
-    graph.add_edge(0,1);
-    graph.add_edge(1,0);
-
-    assert!(graph.has_edge(0, 1));
-    assert!(graph.has_edge(1, 0));
-    assert!(!graph.has_edge(0, 0));
-    assert!(!graph.has_edge(1, 1));
-    assert_eq!(graph.num_edges(), 2);
-}
-
-#[test]
-fn reverse_edge_csr() {
-    let mut graph = EdgeList::new();
-
-    graph.add_edge(0,1);
-    graph.add_edge(1,0);
-
-    let graph = Csr::from_generator(&graph);
-
-    assert!(graph.has_edge(0, 1));
-    assert!(graph.has_edge(1, 0));
-    assert!(!graph.has_edge(0, 0));
-    assert!(!graph.has_edge(1, 1));
-    assert_eq!(graph.num_edges(), 2);
-}
+graph_conformance!(edgelist, EdgeList);
+graph_conformance!(adjacencylist, AdjacencyList);
+static_graph_conformance!(csr, Csr);
 
 #[test]
 fn neighbors_edgelist() {
@@ -350,7 +56,7 @@ fn neighbors_csr() {
 }
 
 #[test]
-fn multi_neighbors_edgelist() {
+fn edges_edgelist() {
     let mut graph = EdgeList::new();
 
     graph.add_edge(0,1);
@@ -361,13 +67,12 @@ fn multi_neighbors_edgelist() {
     graph.add_edge(0,3);
     graph.add_edge(1,2);
 
-    assert_eq!(graph.neighbors(0).collect::<Vec<Node>>(), vec![1,2,2,3,3,3]);
-    assert_eq!(graph.neighbors(1).collect::<Vec<Node>>(), vec![2]);
-    assert_eq!(graph.neighbors(2).collect::<Vec<Node>>(), vec![]);
+    assert_eq!(graph.edges().collect::<Vec<Edge>>()[6], Edge::new(1,2));
+    assert_eq!(graph.edges().collect::<Vec<Edge>>().len(), 7);
 }
 
 #[test]
-fn multi_neighbors_adjacencylist() {
+fn edges_adjacencylist() {
     let mut graph = AdjacencyList::new();
 
     graph.add_edge(0,1);
@@ -378,13 +83,12 @@ fn multi_neighbors_adjacencylist() {
     graph.add_edge(0,3);
     graph.add_edge(1,2);
 
-    assert_eq!(graph.neighbors(0).collect::<Vec<Node>>(), vec![1,2,2,3,3,3]);
-    assert_eq!(graph.neighbors(1).collect::<Vec<Node>>(), vec![2]);
-    assert_eq!(graph.neighbors(2).collect::<Vec<Node>>(), vec![]);
+    assert_eq!(graph.edges().collect::<Vec<Edge>>()[6], Edge::new(1,2));
+    assert_eq!(graph.edges().collect::<Vec<Edge>>().len(), 7);
 }
 
 #[test]
-fn multi_neighbors_csr() {
+fn edges_csr() {
     let mut graph = EdgeList::new();
 
     graph.add_edge(0,1);
@@ -397,59 +101,22 @@ fn multi_neighbors_csr() {
 
     let graph = Csr::from_generator(&graph);
 
-    assert_eq!(graph.neighbors(0).collect::<Vec<Node>>(), vec![1,2,2,3,3,3]);
-    assert_eq!(graph.neighbors(1).collect::<Vec<Node>>(), vec![2]);
-    assert_eq!(graph.neighbors(2).collect::<Vec<Node>>(), vec![]);
-}
-
-#[test]
-fn edges_edgelist() {
-    let mut graph = EdgeList::new();
-
-    graph.add_edge(0,1);
-    graph.add_edge(0,2);
-    graph.add_edge(0,2);
-    graph.add_edge(0,3);
-    graph.add_edge(0,3);
-    graph.add_edge(0,3);
-    graph.add_edge(1,2);
-
-    assert_eq!(graph.edges().collect::<Vec<Edge>>()[6], Edge::new(1,2));
-    assert_eq!(graph.edges().collect::<Vec<Edge>>().len(), 7);
-}
-
-#[test]
-fn edges_adjacencylist() {
-    let mut graph = AdjacencyList::new();
-
-    graph.add_edge(0,1);
-    graph.add_edge(0,2);
-    graph.add_edge(0,2);
-    graph.add_edge(0,3);
-    graph.add_edge(0,3);
-    graph.add_edge(0,3);
-    graph.add_edge(1,2);
-
     assert_eq!(graph.edges().collect::<Vec<Edge>>()[6], Edge::new(1,2));
     assert_eq!(graph.edges().collect::<Vec<Edge>>().len(), 7);
 }
 
 #[test]
-fn edges_csr() {
+fn edges_empty_rows_csr() {
     let mut graph = EdgeList::new();
 
     graph.add_edge(0,1);
-    graph.add_edge(0,2);
-    graph.add_edge(0,2);
-    graph.add_edge(0,3);
-    graph.add_edge(0,3);
-    graph.add_edge(0,3);
-    graph.add_edge(1,2);
+    graph.add_edge(2,3);
+    graph.add_edge(2,0);
+    graph.add_edge(5,4);
 
-    let graph = Csr::from_generator(&graph);
+    let csr = Csr::from_generator(&graph);
 
-    assert_eq!(graph.edges().collect::<Vec<Edge>>()[6], Edge::new(1,2));
-    assert_eq!(graph.edges().collect::<Vec<Edge>>().len(), 7);
+    assert_eq!(csr.edges().collect::<Vec<Edge>>(), graph.edges().collect::<Vec<Edge>>());
 }
 
 #[test]
@@ -535,3 +202,25 @@ fn conversion_bfs() {
 
     assert_eq!(pred_al, pred_el);
 }
+
+#[test]
+fn csr_from_generator_parallel() {
+    let erdos = Erdos::new(1000, 0.15).with_seed(1);
+    let rmat = Rmat::new(11, 40).with_permutation().with_seed(2);
+    let path = Path::new(10);
+
+    let expected = (csr_arrays(&Csr::from_generator(&erdos)), csr_arrays(&Csr::from_generator(&rmat)), csr_arrays(&Csr::from_generator(&path)));
+    for threads in &[1, 2, 3, 8] {
+        assert_eq!(csr_arrays(&Csr::from_generator_parallel(&erdos, *threads)), expected.0);
+        assert_eq!(csr_arrays(&Csr::from_generator_parallel(&rmat, *threads)), expected.1);
+        assert_eq!(csr_arrays(&Csr::from_generator_parallel(&path, *threads)), expected.2);
+    }
+
+    let empty = Csr::from_generator_parallel(&Erdos::new(0, 0.5), 4);
+    assert_eq!(empty.num_nodes(), 0);
+    assert_eq!(empty.num_edges(), 0);
+}
+
+fn csr_arrays(csr: &Csr) -> (Vec<usize>, Vec<Node>) {
+    (csr.row().to_vec(), csr.col().to_vec())
+}
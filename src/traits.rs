@@ -1,4 +1,9 @@
 use algorithms::*;
+use Error;
+use Result;
+
+use std::sync::Mutex;
+use std::thread;
 
 pub type Node = usize;
 
@@ -20,9 +25,82 @@ impl Edge {
 }
 
 pub trait Generator {
-    fn edges<'a>(&'a self) -> Box<Iterator<Item=Edge> + 'a>;
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a>;
+
+    /// Number of nodes the generated graph spans, if known up front. Allows
+    /// representations to keep isolated nodes past the largest edge endpoint.
+    fn num_nodes_hint(&self) -> Option<usize> {
+        None
+    }
+
+    ///
+    /// The edges split into parts that can be generated independently, e.g.
+    /// on separate threads. Concatenated in order, the parts yield the same
+    /// edges as `edges()`. Generators that can't be split return `None`.
+    ///
+    fn edge_chunks<'a>(&'a self) -> Option<Vec<Box<dyn Iterator<Item=Edge> + Send + 'a>>> {
+        None
+    }
+
+    ///
+    /// Generates the edge chunks on up to **threads** threads and returns
+    /// them in order. The result doesn't depend on the number of threads.
+    ///
+    fn edges_parallel(&self, threads: usize) -> Vec<Vec<Edge>> {
+        match self.edge_chunks() {
+            Some(chunks) => generate_chunks(chunks, threads),
+            None => vec![self.edges().collect()],
+        }
+    }
 }
 
+fn generate_chunks<'a>(chunks: Vec<Box<dyn Iterator<Item=Edge> + Send + 'a>>, threads: usize) -> Vec<Vec<Edge>> {
+    let count = chunks.len();
+    let queue = Mutex::new(chunks.into_iter().enumerate());
+    let done = Mutex::new(Vec::with_capacity(count));
+
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, count.max(1)) {
+            scope.spawn(|| loop {
+                let next = queue.lock().unwrap().next();
+                match next {
+                    Some((i, chunk)) => {
+                        let edges = chunk.collect::<Vec<Edge>>();
+                        done.lock().unwrap().push((i, edges));
+                    },
+                    None => break,
+                }
+            });
+        }
+    });
+
+    let mut done = done.into_inner().unwrap();
+    done.sort_by_key(|&(i, _)| i);
+    done.into_iter().map(|(_, edges)| edges).collect()
+}
+
+impl<G: Generator + ?Sized> Generator for &G {
+    fn edges<'a>(&'a self) -> Box<dyn Iterator<Item=Edge> + 'a> {
+        (**self).edges()
+    }
+
+    fn num_nodes_hint(&self) -> Option<usize> {
+        (**self).num_nodes_hint()
+    }
+
+    fn edge_chunks<'a>(&'a self) -> Option<Vec<Box<dyn Iterator<Item=Edge> + Send + 'a>>> {
+        (**self).edge_chunks()
+    }
+}
+
+///
+/// A graph whose nodes are the ids `0..num_nodes()`.
+///
+/// Queries about a node outside of that range behave as if the node had no
+/// edges: `neighbors` is empty and `has_edge` is `false`. Algorithms that
+/// start from a node panic when it is out of range. Each of them has a `try_`
+/// variant that returns `Error::NodeOutOfRange` instead.
+///
 pub trait StaticGraph : Generator {
     fn from_generator<T: Generator>(gen: &T) -> Self;
 
@@ -31,14 +109,33 @@ pub trait StaticGraph : Generator {
 
     fn has_edge(&self, from: Node, to: Node) -> bool;
 
-    fn neighbors<'a>(&'a self, from: Node) -> Box<Iterator<Item=Node> + 'a>;
+    fn neighbors<'a>(&'a self, from: Node) -> Box<dyn Iterator<Item=Node> + 'a>;
 
     fn clear(&mut self);
 
+    fn contains_node(&self, node: Node) -> bool {
+        node < self.num_nodes()
+    }
+
+    fn try_has_edge(&self, from: Node, to: Node) -> Result<bool> {
+        check_node(self, from)?;
+        check_node(self, to)?;
+        Ok(self.has_edge(from, to))
+    }
+
+    fn try_neighbors<'a>(&'a self, from: Node) -> Result<Box<dyn Iterator<Item=Node> + 'a>> {
+        check_node(self, from)?;
+        Ok(self.neighbors(from))
+    }
+
     fn breadth_first_search(&self, start: Node) -> Vec<Option<Node>> where Self: Sized {
         breadth_first_search(self, start)
     }
 
+    fn try_breadth_first_search(&self, start: Node) -> Result<Vec<Option<Node>>> where Self: Sized {
+        try_breadth_first_search(self, start)
+    }
+
     fn is_bipartite(&self) -> bool where Self: Sized {
         is_bipartite(self)
     }
@@ -48,6 +145,14 @@ pub trait StaticGraph : Generator {
     }
 }
 
+pub(crate) fn check_node<T: StaticGraph + ?Sized>(graph: &T, node: Node) -> Result<()> {
+    if graph.contains_node(node) {
+        Ok(())
+    } else {
+        Err(Error::NodeOutOfRange { node, num_nodes: graph.num_nodes() })
+    }
+}
+
 pub trait Graph : StaticGraph {
     fn new() -> Self;
     fn add_edge(&mut self, from: Node, to: Node);